@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin_explorer::{Address, FBlock};
+use chrono::NaiveDate;
+
+use crate::databases::{Batch, Database};
+
+const LEN_KEY: &[u8] = b"len";
+
+///
+/// Maps every address ever used to a sequential id.
+///
+/// Addresses are stored as 8 byte hashes to keep the index small enough for mainnet,
+/// at the cost of a few possible collisions.
+///
+pub struct AddressIndex {
+    database: Database,
+}
+
+impl AddressIndex {
+    pub fn new(path: &str) -> color_eyre::Result<Self> {
+        Ok(Self {
+            database: Database::open(path, "address_index")?,
+        })
+    }
+
+    pub fn get(&self, hash: u64) -> Option<u64> {
+        self.database
+            .get(&hash.to_be_bytes())
+            .map(|id| u64::from_be_bytes(id.as_ref().try_into().unwrap()))
+    }
+
+    pub fn contains(&self, hash: u64) -> bool {
+        self.get(hash).is_some()
+    }
+
+    /// Number of addresses ever used, which is also the next id
+    pub fn count(&self) -> u64 {
+        self.database.get(LEN_KEY).map_or(0, |count| {
+            u64::from_be_bytes(count.as_ref().try_into().unwrap())
+        })
+    }
+
+    pub fn import_daily_blocks(
+        &self,
+        date: &NaiveDate,
+        blocks: &[FBlock],
+    ) -> color_eyre::Result<()> {
+        if self.database.is_date_imported(date) {
+            return Ok(());
+        }
+
+        let mut count = self.count();
+
+        let mut batch = Batch::default();
+
+        let mut new_hashes = HashSet::new();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .flat_map(|tx| tx.output.iter())
+            .flat_map(|txout| txout.addresses.iter())
+            .map(address_to_hash)
+            .for_each(|hash| {
+                if !self.contains(hash) && new_hashes.insert(hash) {
+                    batch.insert(&hash.to_be_bytes(), &count.to_be_bytes());
+
+                    count += 1;
+                }
+            });
+
+        batch.insert(LEN_KEY, &count.to_be_bytes());
+
+        self.database.apply_daily_batch(date, batch)
+    }
+
+    pub fn is_date_imported(&self, date: &NaiveDate) -> bool {
+        self.database.is_date_imported(date)
+    }
+
+    pub fn flush(&self) -> color_eyre::Result<()> {
+        self.database.flush()
+    }
+}
+
+pub fn address_to_hash(address: &Address) -> u64 {
+    let hash = sha256::Hash::hash(address.to_string().as_bytes());
+
+    u64::from_be_bytes(hash[..8].try_into().unwrap())
+}
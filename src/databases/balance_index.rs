@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+
+use crate::{
+    databases::{address_to_hash, Batch, Database},
+    processors::DBCaches,
+};
+
+///
+/// Maps every address (by hash) with a non zero balance to its balance in sats.
+///
+/// Only outputs with a single address are taken into account,
+/// bare multisig and non standard outputs have no owner we can credit.
+///
+pub struct BalanceIndex {
+    database: Database,
+}
+
+impl BalanceIndex {
+    pub fn new(path: &str) -> color_eyre::Result<Self> {
+        Ok(Self {
+            database: Database::open(path, "balance_index")?,
+        })
+    }
+
+    pub fn get(&self, hash: u64) -> u64 {
+        self.database.get(&hash.to_be_bytes()).map_or(0, |balance| {
+            u64::from_be_bytes(balance.as_ref().try_into().unwrap())
+        })
+    }
+
+    pub fn import_daily_blocks(
+        &self,
+        date: &NaiveDate,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+    ) -> color_eyre::Result<()> {
+        if self.database.is_date_imported(date) {
+            return Ok(());
+        }
+
+        let mut batch = Batch::default();
+
+        blocks_to_balance_changes(blocks, db, caches)
+            .into_iter()
+            .try_for_each(|(hash, change)| -> color_eyre::Result<()> {
+                let balance = apply_balance_change(self.get(hash), change, hash, date)?;
+
+                if balance == 0 {
+                    batch.remove(&hash.to_be_bytes());
+                } else {
+                    batch.insert(&hash.to_be_bytes(), &balance.to_be_bytes());
+                }
+
+                Ok(())
+            })?;
+
+        self.database.apply_daily_batch(date, batch)
+    }
+
+    pub fn is_date_imported(&self, date: &NaiveDate) -> bool {
+        self.database.is_date_imported(date)
+    }
+
+    pub fn flush(&self) -> color_eyre::Result<()> {
+        self.database.flush()
+    }
+}
+
+/// New balance of an address, which can't go below zero unless the index and the blocks disagree
+pub fn apply_balance_change(
+    balance: u64,
+    change: i64,
+    hash: u64,
+    date: &NaiveDate,
+) -> color_eyre::Result<u64> {
+    balance.checked_add_signed(change).ok_or_else(|| {
+        eyre!("Balance of address {hash:016x} would go below zero on {date}: {balance} {change:+}")
+    })
+}
+
+/// Net balance change of every address that sent or received during the day
+pub fn blocks_to_balance_changes(
+    blocks: &[FBlock],
+    db: &BitcoinDB,
+    caches: &DBCaches,
+) -> HashMap<u64, i64> {
+    let mut changes: HashMap<u64, i64> = HashMap::new();
+
+    blocks.iter().for_each(|block| {
+        block.txdata.iter().for_each(|tx| {
+            tx.input.iter().for_each(|txin| {
+                let txid = txin.previous_output.txid;
+
+                let vout = txin.previous_output.vout;
+
+                let txprev = caches.txid_to_tx(txid, db);
+
+                let txout = txprev.output.get(vout as usize).unwrap();
+
+                if let [address] = txout.addresses.as_ref() {
+                    *changes.entry(address_to_hash(address)).or_default() -= txout.value as i64;
+                }
+            });
+
+            tx.output.iter().for_each(|txout| {
+                if let [address] = txout.addresses.as_ref() {
+                    *changes.entry(address_to_hash(address)).or_default() += txout.value as i64;
+                }
+            });
+        })
+    });
+
+    changes
+}
@@ -0,0 +1,99 @@
+use std::{cell::RefCell, collections::HashMap, path::Path, str::FromStr};
+
+use chrono::NaiveDate;
+use sled::{Db, IVec};
+
+const DATE_KEY: &[u8] = b"date";
+
+/// Changes of a day, like `sled::Batch` but readable before being applied
+#[derive(Default)]
+pub struct Batch {
+    changes: Vec<(IVec, Option<IVec>)>,
+}
+
+impl Batch {
+    pub fn insert<K: Into<IVec>, V: Into<IVec>>(&mut self, key: K, value: V) {
+        self.changes.push((key.into(), Some(value.into())));
+    }
+
+    pub fn remove<K: Into<IVec>>(&mut self, key: K) {
+        self.changes.push((key.into(), None));
+    }
+}
+
+///
+/// A sled database whose daily batches are kept in memory until flushed,
+/// reads going through them first.
+///
+/// Sled writes its log as its buffers fill up, with or without a flush,
+/// so nothing reaches it between two flushes.
+///
+pub struct Database {
+    db: Db,
+    pending: RefCell<HashMap<IVec, Option<IVec>>>,
+    pending_date: RefCell<Option<NaiveDate>>,
+}
+
+impl Database {
+    pub fn open(path: &str, name: &str) -> color_eyre::Result<Self> {
+        Ok(Self {
+            db: sled::open(Path::new(path).join(name))?,
+            pending: RefCell::new(HashMap::new()),
+            pending_date: RefCell::new(None),
+        })
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<IVec> {
+        if let Some(value) = self.pending.borrow().get(key) {
+            return value.clone();
+        }
+
+        self.db.get(key).unwrap()
+    }
+
+    /// Whether the blocks of that date (or a later one) were already imported, flushed or not
+    pub fn is_date_imported(&self, date: &NaiveDate) -> bool {
+        let last_date = self.pending_date.borrow().or_else(|| {
+            self.db
+                .get(DATE_KEY)
+                .unwrap()
+                .map(|value| NaiveDate::from_str(std::str::from_utf8(&value).unwrap()).unwrap())
+        });
+
+        last_date.is_some_and(|last_date| *date <= last_date)
+    }
+
+    /// Keeps the changes of a day in memory, on top of the previous ones
+    pub fn apply_daily_batch(&self, date: &NaiveDate, batch: Batch) -> color_eyre::Result<()> {
+        self.pending.borrow_mut().extend(batch.changes);
+
+        self.pending_date.replace(Some(*date));
+
+        Ok(())
+    }
+
+    /// Writes every pending day along with the last date, atomically, and waits for the disk
+    pub fn flush(&self) -> color_eyre::Result<()> {
+        let Some(date) = self.pending_date.take() else {
+            return Ok(());
+        };
+
+        let mut batch = sled::Batch::default();
+
+        self.pending
+            .take()
+            .into_iter()
+            .for_each(|(key, value)| match value {
+                Some(value) => batch.insert(key, value),
+                None => batch.remove(key),
+            });
+
+        batch.insert(DATE_KEY, date.to_string().as_bytes());
+
+        self.db.apply_batch(batch)?;
+
+        self.db.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+
+use crate::{
+    databases::{address_to_hash, AddressIndex, Batch, Database},
+    processors::DBCaches,
+    utils::is_coinjoin_like,
+};
+
+///
+/// Union-find over address ids, merging the addresses spent together in a transaction
+/// (common-input-ownership heuristic), CoinJoin-like transactions excepted.
+///
+/// Only non root ids are stored, pointing to their parent, an entity being identified
+/// by the id of its root, which is always its smallest address id.
+///
+pub struct EntityIndex {
+    database: Database,
+}
+
+impl EntityIndex {
+    pub fn new(path: &str) -> color_eyre::Result<Self> {
+        Ok(Self {
+            database: Database::open(path, "entity_index")?,
+        })
+    }
+
+    fn get_parent(&self, id: u64) -> Option<u64> {
+        self.database
+            .get(&id.to_be_bytes())
+            .map(|parent| u64::from_be_bytes(parent.as_ref().try_into().unwrap()))
+    }
+
+    /// Id of the entity the address id belongs to
+    pub fn get(&self, id: u64) -> u64 {
+        let mut id = id;
+
+        while let Some(parent) = self.get_parent(id) {
+            id = parent;
+        }
+
+        id
+    }
+
+    /// Finds the root of an id while compressing its path, pending changes included
+    fn find(&self, id: u64, parents: &mut HashMap<u64, u64>) -> u64 {
+        let mut path = vec![];
+
+        let mut root = id;
+
+        while let Some(parent) = parents
+            .get(&root)
+            .copied()
+            .or_else(|| self.get_parent(root))
+        {
+            path.push(root);
+
+            root = parent;
+        }
+
+        path.into_iter().for_each(|id| {
+            parents.insert(id, root);
+        });
+
+        root
+    }
+
+    pub fn import_daily_blocks(
+        &self,
+        date: &NaiveDate,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        address_index: &AddressIndex,
+    ) -> color_eyre::Result<()> {
+        if self.database.is_date_imported(date) {
+            return Ok(());
+        }
+
+        let mut parents = HashMap::new();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .filter(|tx| tx.input.len() >= 2 && !is_coinjoin_like(tx))
+            .for_each(|tx| {
+                let roots = tx
+                    .input
+                    .iter()
+                    .filter_map(|txin| {
+                        let txid = txin.previous_output.txid;
+
+                        let vout = txin.previous_output.vout;
+
+                        let txprev = caches.txid_to_tx(txid, db);
+
+                        let txout = txprev.output.get(vout as usize).unwrap();
+
+                        match txout.addresses.as_ref() {
+                            [address] => address_index.get(address_to_hash(address)),
+                            _ => None,
+                        }
+                    })
+                    .map(|id| self.find(id, &mut parents))
+                    .collect::<Vec<_>>();
+
+                if let Some(min) = roots.iter().min().copied() {
+                    roots
+                        .into_iter()
+                        .filter(|root| *root != min)
+                        .for_each(|root| {
+                            parents.insert(root, min);
+                        });
+                }
+            });
+
+        let mut batch = Batch::default();
+
+        parents.into_iter().for_each(|(id, parent)| {
+            batch.insert(&id.to_be_bytes(), &parent.to_be_bytes());
+        });
+
+        self.database.apply_daily_batch(date, batch)
+    }
+
+    pub fn is_date_imported(&self, date: &NaiveDate) -> bool {
+        self.database.is_date_imported(date)
+    }
+
+    pub fn flush(&self) -> color_eyre::Result<()> {
+        self.database.flush()
+    }
+}
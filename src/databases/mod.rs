@@ -0,0 +1,79 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+
+use crate::processors::DBCaches;
+
+mod address_index;
+mod balance_index;
+mod database;
+mod entity_index;
+mod spend_index;
+
+pub use address_index::*;
+pub use balance_index::*;
+pub use database::*;
+pub use entity_index::*;
+pub use spend_index::*;
+
+///
+/// Persistent indexes built while scanning the chain.
+///
+/// While processors run, they contain the state as of the end of the previous day,
+/// the blocks of the day are imported afterwards.
+///
+/// The daily changes are kept in memory and only written when flushed, along with the outputs
+/// of the processors, so that both are always saved as of the same day, even after a crash.
+///
+pub struct Databases {
+    pub address_index: AddressIndex,
+    pub balance_index: BalanceIndex,
+    pub entity_index: EntityIndex,
+    pub spend_index: SpendIndex,
+}
+
+impl Databases {
+    pub fn new(path: &str) -> color_eyre::Result<Self> {
+        Ok(Self {
+            address_index: AddressIndex::new(path)?,
+            balance_index: BalanceIndex::new(path)?,
+            entity_index: EntityIndex::new(path)?,
+            spend_index: SpendIndex::new(path)?,
+        })
+    }
+
+    pub fn import_daily_blocks(
+        &self,
+        date: NaiveDate,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+    ) -> color_eyre::Result<()> {
+        self.address_index.import_daily_blocks(&date, blocks)?;
+
+        self.balance_index
+            .import_daily_blocks(&date, blocks, db, caches)?;
+
+        self.entity_index
+            .import_daily_blocks(&date, blocks, db, caches, &self.address_index)?;
+
+        self.spend_index.import_daily_blocks(&date, blocks, db)
+    }
+
+    /// Whether any index already imported the blocks of that date (or a later one)
+    pub fn is_date_imported(&self, date: &NaiveDate) -> bool {
+        self.address_index.is_date_imported(date)
+            || self.balance_index.is_date_imported(date)
+            || self.entity_index.is_date_imported(date)
+            || self.spend_index.is_date_imported(date)
+    }
+
+    pub fn flush(&self) -> color_eyre::Result<()> {
+        self.address_index.flush()?;
+
+        self.balance_index.flush()?;
+
+        self.entity_index.flush()?;
+
+        self.spend_index.flush()
+    }
+}
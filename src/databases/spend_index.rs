@@ -0,0 +1,95 @@
+use bitcoin::hashes::Hash;
+use bitcoin_explorer::{BitcoinDB, FBlock, Txid};
+use chrono::NaiveDate;
+
+use crate::databases::{Batch, Database};
+
+/// Where an output went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spend {
+    pub txid: Txid,
+    pub vin: u32,
+    pub height: usize,
+}
+
+///
+/// Maps every spent outpoint to the transaction spending it, the index of the input
+/// and the height of the block it was included in.
+///
+/// The opposite of what `DBCaches` can do, which only goes from an input to its prevout.
+///
+pub struct SpendIndex {
+    database: Database,
+}
+
+impl SpendIndex {
+    pub fn new(path: &str) -> color_eyre::Result<Self> {
+        Ok(Self {
+            database: Database::open(path, "spend_index")?,
+        })
+    }
+
+    /// Spend of the output, `None` if it's unspent as of the last imported date
+    pub fn get(&self, txid: Txid, vout: u32) -> Option<Spend> {
+        self.database
+            .get(&outpoint_to_key(txid, vout))
+            .map(|value| {
+                let value = value.as_ref();
+
+                Spend {
+                    txid: Txid::from_slice(&value[..32]).unwrap(),
+                    vin: u32::from_be_bytes(value[32..36].try_into().unwrap()),
+                    height: u32::from_be_bytes(value[36..40].try_into().unwrap()) as usize,
+                }
+            })
+    }
+
+    pub fn import_daily_blocks(
+        &self,
+        date: &NaiveDate,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+    ) -> color_eyre::Result<()> {
+        if self.database.is_date_imported(date) {
+            return Ok(());
+        }
+
+        let mut batch = Batch::default();
+
+        blocks.iter().for_each(|block| {
+            let height = db.get_height_from_hash(&block.header.block_hash).unwrap() as u32;
+
+            block.txdata.iter().for_each(|tx| {
+                tx.input.iter().enumerate().for_each(|(vin, txin)| {
+                    let key = outpoint_to_key(txin.previous_output.txid, txin.previous_output.vout);
+
+                    let mut value = tx.txid.into_inner().to_vec();
+
+                    value.extend((vin as u32).to_be_bytes());
+                    value.extend(height.to_be_bytes());
+
+                    batch.insert(key, value);
+                });
+            });
+        });
+
+        self.database.apply_daily_batch(date, batch)
+    }
+
+    pub fn is_date_imported(&self, date: &NaiveDate) -> bool {
+        self.database.is_date_imported(date)
+    }
+
+    pub fn flush(&self) -> color_eyre::Result<()> {
+        self.database.flush()
+    }
+}
+
+/// Txid followed by the big endian vout, 36 bytes
+fn outpoint_to_key(txid: Txid, vout: u32) -> Vec<u8> {
+    let mut key = txid.into_inner().to_vec();
+
+    key.extend(vout.to_be_bytes());
+
+    key
+}
@@ -1,28 +1,43 @@
-use bitcoin_explorer::{BitcoinDB, FBlock};
+use bitcoin_explorer::{BitcoinDB, FBlock, Txid};
+use chrono::{Datelike, NaiveDate};
 use itertools::Itertools;
-use std::{path::Path, time::Instant};
+use std::{env, path::Path, str::FromStr, time::Instant};
 
+mod databases;
 mod output;
 mod processors;
 mod utils;
 
+use databases::*;
 use processors::*;
 use utils::*;
 
+const BITCOIN_PATH: &str = "/Volumes/t7s/bitcoin";
+
+const JSONS_PATH: &str = "./jsons";
+
+const DATABASES_PATH: &str = "./databases";
+
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
-    let timer = Instant::now();
+    let args = env::args().collect::<Vec<_>>();
 
-    let path = Path::new("/Volumes/t7s/bitcoin");
+    match args.as_slice() {
+        [_, command, txid, vout] if command == "spend" => return print_spend(txid, vout),
+        [_, command, snapshot] if command == "verify" => return verify(snapshot),
+        _ => {}
+    }
 
-    let db = BitcoinDB::new(path, true)?;
+    let timer = Instant::now();
+
+    let db = BitcoinDB::new(Path::new(BITCOIN_PATH), true)?;
 
     let block_count = db.get_block_count();
 
     println!("\n{block_count} blocks found.");
 
-    let path = "./jsons";
+    let path = JSONS_PATH;
 
     let processors: Vec<Box<dyn DailyBlocksImporterPlusOutputExporter>> = vec![
         Box::new(BlocksCounterProcessor::new(path)),
@@ -33,34 +48,115 @@ fn main() -> color_eyre::Result<()> {
         Box::new(FeesCounterProcessor::new(path)),
         Box::new(CoinbasesCounterProcessor::new(path)),
         Box::new(VolumeCounterProcessor::new(path)),
+        Box::new(DifficultyProcessor::new(path)),
+        Box::new(BlockIntervalsProcessor::new(path)),
+        Box::new(SubsidyProcessor::new(path)),
+        Box::new(SupplyProcessor::new(path)),
+        Box::new(ActiveAddressesProcessor::new(path)),
+        Box::new(BalanceCohortsProcessor::new(path)),
+        Box::new(ActiveEntitiesProcessor::new(path)),
+        Box::new(TransactionPatternsProcessor::new(path)),
+        Box::new(CoinJoinsProcessor::new(path)),
+        Box::new(InscriptionsProcessor::new(path)),
+        Box::new(OpReturnsProcessor::new(path)),
+        Box::new(SegwitAdoptionProcessor::new(path)),
+        Box::new(WalletFingerprintsProcessor::new(path)),
+        Box::new(ScriptTemplatesProcessor::new(path)),
+        Box::new(LightningProcessor::new(path)),
+        Box::new(LifespansProcessor::new(path)),
+        Box::new(LivelinessProcessor::new(path)),
+        Box::new(PreciseDaysDestroyedProcessor::new(path)),
+        Box::new(DustProcessor::new(path)),
+        Box::new(UtxoSetProcessor::new(path)),
+        Box::new(LeaderboardProcessor::new(path)),
+        Box::new(WhalesProcessor::new(path)),
+        Box::new(EconomicVolumeProcessor::new(path)),
     ];
 
+    let databases = Databases::new(DATABASES_PATH)?;
+
     let mut caches = DBCaches::new();
 
     // Don't change max or last data saved will be inacurate
     db.iter_block::<FBlock>(0, block_count)
         .batching(create_group_blocks_by_day_closure())
-        .for_each(|blocks| {
+        .try_for_each(|blocks| -> color_eyre::Result<()> {
             let first = blocks.first().unwrap();
 
             let date = timestamp_to_naive_date(first.header.time);
 
             println!("\n{date}...");
 
-            processors.iter().for_each(|processor| {
-                processor
-                    .import_daily_blocks(date, &blocks, &db, &caches)
-                    .unwrap()
-            });
+            processors.iter().try_for_each(|processor| {
+                processor.import_daily_blocks(date, &blocks, &db, &caches, &databases)
+            })?;
+
+            databases.import_daily_blocks(date, &blocks, &db, &caches)?;
 
             caches.clear();
-        });
 
-    // processors
-    //     .iter()
-    //     .for_each(|processor| processor.export_output().unwrap());
+            if is_checkpoint(&date) {
+                save(&processors, &databases)?;
+            }
+
+            Ok(())
+        })?;
+
+    save(&processors, &databases)?;
 
     println!("Done in {} seconds", timer.elapsed().as_secs_f32());
 
     Ok(())
 }
+
+fn is_checkpoint(date: &NaiveDate) -> bool {
+    let day = date.day();
+
+    day == 1 || day == 14
+}
+
+///
+/// Exports the processors and flushes the databases together,
+/// so that after a restart both describe the same last day.
+///
+/// Outputs go first: if interrupted in between, the databases are behind
+/// and simply import again the days the processors skip.
+///
+fn save(
+    processors: &[Box<dyn DailyBlocksImporterPlusOutputExporter>],
+    databases: &Databases,
+) -> color_eyre::Result<()> {
+    println!("Saving");
+
+    processors
+        .iter()
+        .try_for_each(|processor| processor.export_output())?;
+
+    databases.flush()
+}
+
+/// Where did this output go, according to the spend index built by previous scans
+fn print_spend(txid: &str, vout: &str) -> color_eyre::Result<()> {
+    let txid = Txid::from_str(txid)?;
+
+    let vout = vout.parse::<u32>()?;
+
+    let databases = Databases::new(DATABASES_PATH)?;
+
+    match databases.spend_index.get(txid, vout) {
+        Some(spend) => println!(
+            "{txid}:{vout} was spent by input {} of {} at height {}",
+            spend.vin, spend.txid, spend.height
+        ),
+        None => println!("{txid}:{vout} is unspent as of the last imported day"),
+    }
+
+    Ok(())
+}
+
+/// Compares the UTXO set series with a snapshot written by `bitcoin-cli dumptxoutset`
+fn verify(snapshot: &str) -> color_eyre::Result<()> {
+    let db = BitcoinDB::new(Path::new(BITCOIN_PATH), true)?;
+
+    UtxoSetProcessor::new(JSONS_PATH).verify_snapshot(Path::new(snapshot), &db)
+}
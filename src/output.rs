@@ -5,6 +5,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use chrono::NaiveDate;
 use color_eyre::eyre::eyre;
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -28,6 +29,21 @@ where
         s
     }
 
+    /// Returns a copy of the value of the closest date before the given one
+    pub fn get_previous(&self, date: &NaiveDate) -> Option<T>
+    where
+        T: Clone,
+    {
+        let date = date.to_string();
+
+        let data = self.data.borrow();
+
+        data.iter()
+            .filter(|(key, _)| **key < date)
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, value)| value.clone())
+    }
+
     fn create_dir_all(&self) -> color_eyre::Result<()> {
         let path_parent = self
             .path
@@ -0,0 +1,91 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    databases::{address_to_hash, Databases},
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct ActiveAddresses {
+    sending: usize,
+    receiving: usize,
+    active: usize,
+    new: u64,
+    total: u64,
+}
+
+pub type ActiveAddressesProcessor = Processor<ActiveAddresses, ActiveAddresses>;
+
+impl ActiveAddressesProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "ActiveAddresses".to_string(),
+            output: Output::new(path, "active_addresses.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<ActiveAddresses> for ActiveAddressesProcessor {
+    ///
+    /// Addresses are counted by hash, like in the address index.
+    ///
+    /// An address has to receive before it can send,
+    /// so new addresses are the receiving ones that aren't in the index yet.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        databases: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<ActiveAddresses> {
+        let mut sending = HashSet::new();
+
+        let mut receiving = HashSet::new();
+
+        blocks.iter().for_each(|block| {
+            block.txdata.iter().for_each(|tx| {
+                tx.input.iter().for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    let txprev = self.txid_to_tx(txid, db, caches);
+
+                    let txout = txprev.output.get(vout as usize).unwrap();
+
+                    sending.extend(txout.addresses.iter().map(address_to_hash));
+                });
+
+                tx.output.iter().for_each(|txout| {
+                    receiving.extend(txout.addresses.iter().map(address_to_hash));
+                });
+            })
+        });
+
+        let address_index = &databases.address_index;
+
+        let new = receiving
+            .iter()
+            .filter(|hash| !address_index.contains(**hash))
+            .count() as u64;
+
+        Ok(ActiveAddresses {
+            sending: sending.len(),
+            receiving: receiving.len(),
+            active: sending.union(&receiving).count(),
+            new,
+            total: address_index.count() + new,
+        })
+    }
+
+    fn reads_databases(&self) -> bool {
+        true
+    }
+}
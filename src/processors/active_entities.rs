@@ -0,0 +1,98 @@
+use bitcoin_explorer::{Address, BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    databases::{address_to_hash, Databases},
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct ActiveEntities {
+    sending: usize,
+    receiving: usize,
+    active: usize,
+}
+
+pub type ActiveEntitiesProcessor = Processor<ActiveEntities, ActiveEntities>;
+
+impl ActiveEntitiesProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "ActiveEntities".to_string(),
+            output: Output::new(path, "active_entities.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+/// An entity id, or the hash of an address that isn't in the index yet
+#[derive(PartialEq, Eq, Hash)]
+enum EntityKey {
+    Entity(u64),
+    Unindexed(u64),
+}
+
+impl DailyBlocksProcessor<ActiveEntities> for ActiveEntitiesProcessor {
+    ///
+    /// Same as active addresses but with addresses grouped by entity,
+    /// as clustered up to the end of the previous day.
+    ///
+    /// Addresses first seen today are counted as their own entity.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        databases: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<ActiveEntities> {
+        let address_to_entity_key = |address: &Address| {
+            let hash = address_to_hash(address);
+
+            databases
+                .address_index
+                .get(hash)
+                .map_or(EntityKey::Unindexed(hash), |id| {
+                    EntityKey::Entity(databases.entity_index.get(id))
+                })
+        };
+
+        let mut sending = HashSet::new();
+
+        let mut receiving = HashSet::new();
+
+        blocks.iter().for_each(|block| {
+            block.txdata.iter().for_each(|tx| {
+                tx.input.iter().for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    let txprev = self.txid_to_tx(txid, db, caches);
+
+                    let txout = txprev.output.get(vout as usize).unwrap();
+
+                    sending.extend(txout.addresses.iter().map(address_to_entity_key));
+                });
+
+                tx.output.iter().for_each(|txout| {
+                    receiving.extend(txout.addresses.iter().map(address_to_entity_key));
+                });
+            })
+        });
+
+        Ok(ActiveEntities {
+            sending: sending.len(),
+            receiving: receiving.len(),
+            active: sending.union(&receiving).count(),
+        })
+    }
+
+    fn reads_databases(&self) -> bool {
+        true
+    }
+}
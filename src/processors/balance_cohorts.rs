@@ -0,0 +1,122 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::{apply_balance_change, blocks_to_balance_changes, Databases},
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+/// Names and upper bounds (excluded, in sats) of the cohorts, the last one having none
+const COHORTS: [(&str, u64); 9] = [
+    ("<0.001", 100_000),
+    ("0.001-0.01", 1_000_000),
+    ("0.01-0.1", 10_000_000),
+    ("0.1-1", 100_000_000),
+    ("1-10", 1_000_000_000),
+    ("10-100", 10_000_000_000),
+    ("100-1k", 100_000_000_000),
+    ("1k-10k", 1_000_000_000_000),
+    (">10k", u64::MAX),
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BalanceCohort {
+    name: String,
+    addresses: u64,
+    supply: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BalanceCohorts {
+    cohorts: Vec<BalanceCohort>,
+}
+
+pub type BalanceCohortsProcessor = Processor<BalanceCohorts, BalanceCohorts>;
+
+impl BalanceCohortsProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "BalanceCohorts".to_string(),
+            output: Output::new(path, "balance_cohorts.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<BalanceCohorts> for BalanceCohortsProcessor {
+    ///
+    /// Number of addresses and supply held per balance band, at the end of the day.
+    ///
+    /// Cohorts are carried over from the previous day and only the addresses
+    /// whose balance changed are moved from one band to another.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        databases: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<BalanceCohorts> {
+        let mut cohorts = self.output.get_previous(date).map_or_else(
+            || {
+                COHORTS
+                    .iter()
+                    .map(|(name, _)| BalanceCohort {
+                        name: name.to_string(),
+                        addresses: 0,
+                        supply: 0,
+                    })
+                    .collect()
+            },
+            |previous| previous.cohorts,
+        );
+
+        blocks_to_balance_changes(blocks, db, caches)
+            .into_iter()
+            .try_for_each(|(hash, change)| -> color_eyre::Result<()> {
+                let previous_balance = databases.balance_index.get(hash);
+
+                let balance = apply_balance_change(previous_balance, change, hash, date)?;
+
+                if previous_balance > 0 {
+                    let cohort = &mut cohorts[balance_to_cohort_index(previous_balance)];
+
+                    let missing =
+                        || eyre!("Address {hash:016x} is missing from its cohort on {date}");
+
+                    cohort.addresses = cohort.addresses.checked_sub(1).ok_or_else(missing)?;
+                    cohort.supply = cohort
+                        .supply
+                        .checked_sub(previous_balance)
+                        .ok_or_else(missing)?;
+                }
+
+                if balance > 0 {
+                    let cohort = &mut cohorts[balance_to_cohort_index(balance)];
+
+                    cohort.addresses += 1;
+                    cohort.supply += balance;
+                }
+
+                Ok(())
+            })?;
+
+        Ok(BalanceCohorts { cohorts })
+    }
+
+    fn reads_databases(&self) -> bool {
+        true
+    }
+}
+
+fn balance_to_cohort_index(balance: u64) -> usize {
+    COHORTS
+        .iter()
+        .position(|(_, upper_bound)| balance < *upper_bound)
+        .unwrap_or(COHORTS.len() - 1)
+}
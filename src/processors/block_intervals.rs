@@ -0,0 +1,102 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+const ONE_HOUR_IN_SECONDS: i64 = 60 * 60;
+
+#[derive(Serialize, Deserialize)]
+pub struct BlockIntervals {
+    mean: f64,
+    median: f64,
+    max: i64,
+    over_an_hour: usize,
+    negative: usize,
+}
+
+pub type BlockIntervalsProcessor = Processor<BlockIntervals, BlockIntervals>;
+
+impl BlockIntervalsProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "BlockIntervals".to_string(),
+            output: Output::new(path, "block_intervals.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<BlockIntervals> for BlockIntervalsProcessor {
+    ///
+    /// Intervals are in seconds between a block and the one before it,
+    /// the first block of the day being compared to the last one of the previous day.
+    ///
+    /// Block timestamps aren't monotonic so intervals can be negative.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        _: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<BlockIntervals> {
+        let height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let mut previous_time = height
+            .checked_sub(1)
+            .map(|height| self.height_to_header(height, db).time);
+
+        let mut intervals = blocks
+            .iter()
+            .filter_map(|block| {
+                let time = block.header.time;
+
+                let interval = previous_time.map(|previous| i64::from(time) - i64::from(previous));
+
+                previous_time = Some(time);
+
+                interval
+            })
+            .collect::<Vec<_>>();
+
+        if intervals.is_empty() {
+            return Ok(BlockIntervals {
+                mean: 0.0,
+                median: 0.0,
+                max: 0,
+                over_an_hour: 0,
+                negative: 0,
+            });
+        }
+
+        intervals.sort_unstable();
+
+        let len = intervals.len();
+
+        let mean = intervals.iter().sum::<i64>() as f64 / len as f64;
+
+        let median = if len.is_multiple_of(2) {
+            (intervals[len / 2 - 1] + intervals[len / 2]) as f64 / 2.0
+        } else {
+            intervals[len / 2] as f64
+        };
+
+        Ok(BlockIntervals {
+            mean,
+            median,
+            max: *intervals.last().unwrap(),
+            over_an_hour: intervals
+                .iter()
+                .filter(|interval| **interval > ONE_HOUR_IN_SECONDS)
+                .count(),
+            negative: intervals.iter().filter(|interval| **interval < 0).count(),
+        })
+    }
+}
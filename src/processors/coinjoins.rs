@@ -0,0 +1,122 @@
+use bitcoin_explorer::{parser::script::ScriptType, BitcoinDB, FBlock, FTransaction};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::tx_to_most_common_output_value,
+};
+
+/// Pool denominations in sats (0.001, 0.01, 0.05 and 0.5 BTC)
+const WHIRLPOOL_DENOMINATIONS: [u64; 4] = [100_000, 1_000_000, 5_000_000, 50_000_000];
+
+const WHIRLPOOL_PARTICIPANTS: usize = 5;
+
+const WASABI_COORDINATOR_ADDRESSES: [&str; 2] = [
+    "bc1qs604c7jv6amk4cxqlnvuxv26hv3e48cds4m0ew",
+    "bc1qa24tsgchvuxsaccp8vrnkfd85hrcpafg20kmjw",
+];
+
+const WASABI_MIN_EQUAL_OUTPUTS: usize = 10;
+
+const JOINMARKET_MIN_EQUAL_OUTPUTS: usize = 3;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct CoinJoinCounter {
+    count: usize,
+    volume: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct CoinJoins {
+    whirlpool: CoinJoinCounter,
+    wasabi: CoinJoinCounter,
+    joinmarket: CoinJoinCounter,
+}
+
+pub type CoinJoinsProcessor = Processor<CoinJoins, CoinJoins>;
+
+impl CoinJoinsProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "CoinJoins".to_string(),
+            output: Output::new(path, "coinjoins.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+fn is_whirlpool(tx: &FTransaction, value: u64, count: usize) -> bool {
+    tx.input.len() == WHIRLPOOL_PARTICIPANTS
+        && tx.output.len() == WHIRLPOOL_PARTICIPANTS
+        && count == WHIRLPOOL_PARTICIPANTS
+        && WHIRLPOOL_DENOMINATIONS.contains(&value)
+}
+
+/// Pays a known coordinator, or has many equal bech32 outputs like every Wasabi round
+fn is_wasabi(tx: &FTransaction, count: usize) -> bool {
+    let pays_coordinator = tx.output.iter().any(|txout| {
+        txout
+            .addresses
+            .iter()
+            .any(|address| WASABI_COORDINATOR_ADDRESSES.contains(&address.to_string().as_str()))
+    });
+
+    let all_bech32 = tx
+        .output
+        .iter()
+        .all(|txout| txout.script_type == ScriptType::Pay2WitnessPublicKeyHash);
+
+    count >= WASABI_MIN_EQUAL_OUTPUTS && (pays_coordinator || all_bech32)
+}
+
+/// One equal output per participant and at most one change output each
+fn is_joinmarket(tx: &FTransaction, count: usize) -> bool {
+    count >= JOINMARKET_MIN_EQUAL_OUTPUTS && tx.input.len() >= count && tx.output.len() <= 2 * count
+}
+
+impl DailyBlocksProcessor<CoinJoins> for CoinJoinsProcessor {
+    ///
+    /// Detects CoinJoins by the structural fingerprints of the main implementations,
+    /// the volume being the sum of the equal (mixed) outputs.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        _: &BitcoinDB,
+        _: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<CoinJoins> {
+        let mut coinjoins = CoinJoins::default();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .filter(|tx| !tx.input.is_empty())
+            .for_each(|tx| {
+                let (value, count) = match tx_to_most_common_output_value(tx) {
+                    Some((value, count)) if count >= 2 => (value, count),
+                    _ => return,
+                };
+
+                let counter = if is_whirlpool(tx, value, count) {
+                    &mut coinjoins.whirlpool
+                } else if is_wasabi(tx, count) {
+                    &mut coinjoins.wasabi
+                } else if is_joinmarket(tx, count) {
+                    &mut coinjoins.joinmarket
+                } else {
+                    return;
+                };
+
+                counter.count += 1;
+                counter.volume += value * count as u64;
+            });
+
+        Ok(coinjoins)
+    }
+}
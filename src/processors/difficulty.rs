@@ -0,0 +1,105 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct Difficulty {
+    difficulty: f64,
+    hashrate: f64,
+    adjustments: Vec<f64>,
+}
+
+pub type DifficultyProcessor = Processor<Difficulty, Difficulty>;
+
+impl DifficultyProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Difficulty".to_string(),
+            output: Output::new(path, "difficulty.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<Difficulty> for DifficultyProcessor {
+    ///
+    /// Computes the:
+    /// - Difficulty of the last block of the day
+    /// - Estimated hashrate (hashes per second), from the expected work of the blocks
+    ///   and the time elapsed since the last block of the previous day
+    /// - Difficulty adjustments, as a % change from the previous target
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        _: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<Difficulty> {
+        let first = blocks.first().unwrap();
+
+        let height = self.block_to_height(first, db);
+
+        let previous_header = height
+            .checked_sub(1)
+            .map(|height| self.height_to_header(height, db));
+
+        let mut previous_bits = previous_header.map(|header| header.bits);
+
+        let mut adjustments = vec![];
+
+        blocks.iter().for_each(|block| {
+            let bits = block.header.bits;
+
+            if let Some(previous_bits) = previous_bits.filter(|previous| *previous != bits) {
+                let previous_difficulty = bits_to_difficulty(previous_bits);
+
+                let change = (bits_to_difficulty(bits) / previous_difficulty - 1.0) * 100.0;
+
+                adjustments.push(change);
+            }
+
+            previous_bits = Some(bits);
+        });
+
+        let last = blocks.last().unwrap();
+
+        let start_time = previous_header.map_or(first.header.time, |header| header.time);
+
+        let elapsed = i64::from(last.header.time) - i64::from(start_time);
+
+        let hashrate = if elapsed > 0 {
+            let hashes = blocks
+                .iter()
+                .map(|block| bits_to_difficulty(block.header.bits) * 2_f64.powi(32))
+                .sum::<f64>();
+
+            hashes / elapsed as f64
+        } else {
+            0.0
+        };
+
+        Ok(Difficulty {
+            difficulty: bits_to_difficulty(last.header.bits),
+            hashrate,
+            adjustments,
+        })
+    }
+}
+
+/// Converts a compact target (`nBits`) to a difficulty relative to the genesis target.
+fn bits_to_difficulty(bits: u32) -> f64 {
+    let exponent = (bits >> 24) as i32;
+
+    let mantissa = f64::from(bits & 0x007f_ffff);
+
+    f64::from(0xffff) / mantissa * 256_f64.powi(0x1d - exponent)
+}
@@ -0,0 +1,189 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    marker::PhantomData,
+};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{is_uneconomical, script_to_dust_threshold},
+};
+
+/// Environment variable overriding the uneconomical fee rates, comma separated (`DUST_FEE_RATES=2,20`)
+const FEE_RATES_VAR: &str = "DUST_FEE_RATES";
+
+/// Fee rates (in sats per vbyte) at which outputs are checked for being uneconomical to spend
+const DEFAULT_UNECONOMICAL_FEE_RATES: [u64; 5] = [1, 5, 10, 50, 100];
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct DustCounter {
+    count: usize,
+    value: u64,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct DustFlows {
+    created: DustCounter,
+    spent: DustCounter,
+    unspent: DustCounter,
+}
+
+impl DustFlows {
+    /// Only the running unspent counter carries over to the next day
+    fn from_previous(previous: Option<&DustFlows>) -> Self {
+        Self {
+            unspent: previous
+                .map_or_else(DustCounter::default, |previous| previous.unspent.clone()),
+            ..Default::default()
+        }
+    }
+
+    fn create(&mut self, value: u64) {
+        self.created.count += 1;
+        self.created.value += value;
+    }
+
+    fn spend(&mut self, value: u64) {
+        self.spent.count += 1;
+        self.spent.value += value;
+    }
+
+    fn update_unspent(&mut self) {
+        self.unspent.count = self.unspent.count + self.created.count - self.spent.count;
+        self.unspent.value = self.unspent.value + self.created.value - self.spent.value;
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Dust {
+    standard: DustFlows,
+    uneconomical: BTreeMap<u64, DustFlows>,
+}
+
+pub type DustProcessor = Processor<Dust, Dust>;
+
+impl DustProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Dust".to_string(),
+            output: Output::new(path, "dust.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+fn uneconomical_fee_rates() -> color_eyre::Result<BTreeSet<u64>> {
+    let Ok(fee_rates) = env::var(FEE_RATES_VAR) else {
+        return Ok(BTreeSet::from(DEFAULT_UNECONOMICAL_FEE_RATES));
+    };
+
+    fee_rates
+        .split(',')
+        .map(|fee_rate| {
+            fee_rate
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| eyre!("{FEE_RATES_VAR} has an invalid fee rate: {fee_rate:?}"))
+        })
+        .collect()
+}
+
+impl DailyBlocksProcessor<Dust> for DustProcessor {
+    ///
+    /// Outputs below Bitcoin Core's dust threshold of their script type, created and spent,
+    /// with the running number and value of those still unspent.
+    ///
+    /// The same for uneconomical outputs, keyed by fee rate in sats per vbyte,
+    /// an output being uneconomical when spending it would cost more than its value.
+    ///
+    /// Fee rates can be set with `DUST_FEE_RATES`, the output has to be rebuilt
+    /// when they change since their unspent counters start from the first day.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<Dust> {
+        let previous = self.output.get_previous(date);
+
+        let fee_rates = uneconomical_fee_rates()?;
+
+        if let Some(previous) = previous.as_ref() {
+            if !previous.uneconomical.keys().eq(fee_rates.iter()) {
+                return Err(eyre!(
+                    "{FEE_RATES_VAR} changed since the previous day, delete dust.json to rebuild it"
+                ));
+            }
+        }
+
+        let mut dust = Dust {
+            standard: DustFlows::from_previous(
+                previous.as_ref().map(|previous| &previous.standard),
+            ),
+            uneconomical: fee_rates
+                .into_iter()
+                .map(|fee_rate| {
+                    let previous = previous
+                        .as_ref()
+                        .and_then(|previous| previous.uneconomical.get(&fee_rate));
+
+                    (fee_rate, DustFlows::from_previous(previous))
+                })
+                .collect(),
+        };
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .for_each(|tx| {
+                tx.input.iter().for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    let txout = self.outpoint_to_txout(txid, vout, db, caches);
+
+                    if txout.value < script_to_dust_threshold(&txout.script_pubkey) {
+                        dust.standard.spend(txout.value);
+                    }
+
+                    dust.uneconomical
+                        .iter_mut()
+                        .filter(|(fee_rate, _)| {
+                            is_uneconomical(txout.value, &txout.script_pubkey, **fee_rate)
+                        })
+                        .for_each(|(_, flows)| flows.spend(txout.value));
+                });
+
+                tx.output.iter().for_each(|txout| {
+                    if txout.value < script_to_dust_threshold(&txout.script_pubkey) {
+                        dust.standard.create(txout.value);
+                    }
+
+                    dust.uneconomical
+                        .iter_mut()
+                        .filter(|(fee_rate, _)| {
+                            is_uneconomical(txout.value, &txout.script_pubkey, **fee_rate)
+                        })
+                        .for_each(|(_, flows)| flows.create(txout.value));
+                });
+            });
+
+        dust.standard.update_unspent();
+
+        dust.uneconomical
+            .values_mut()
+            .for_each(|flows| flows.update_unspent());
+
+        Ok(dust)
+    }
+}
@@ -0,0 +1,195 @@
+use bitcoin_explorer::{BitcoinDB, FBlock, FTransaction, FTxOut};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    databases::{address_to_hash, Databases},
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+/// Payments are often round amounts in bitcoins, here multiples of 0.001
+const ROUND_AMOUNT_UNIT: u64 = 100_000;
+
+///
+/// Volume removed by each heuristic, there is deliberately none for the output spent first,
+/// see the processor.
+///
+#[derive(Serialize, Deserialize, Default)]
+pub struct RemovedVolume {
+    self_transfer: u64,
+    address_reuse: u64,
+    script_type_match: u64,
+    round_amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct EconomicVolume {
+    volume: u64,
+    adjusted_volume: u64,
+    removed: RemovedVolume,
+}
+
+pub type EconomicVolumeProcessor = Processor<EconomicVolume, EconomicVolume>;
+
+impl EconomicVolumeProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "EconomicVolume".to_string(),
+            output: Output::new(path, "economic_volume.json"),
+            pd: PhantomData,
+        }
+    }
+
+    fn tx_to_prevouts(&self, tx: &FTransaction, db: &BitcoinDB, caches: &DBCaches) -> Vec<FTxOut> {
+        tx.input
+            .iter()
+            .map(|txin| {
+                let txid = txin.previous_output.txid;
+
+                let vout = txin.previous_output.vout;
+
+                self.outpoint_to_txout(txid, vout, db, caches)
+            })
+            .collect()
+    }
+
+    /// Every output goes to an address of an entity that sent, as clustered up to the previous day
+    fn is_self_transfer(
+        &self,
+        tx: &FTransaction,
+        prevouts: &[FTxOut],
+        databases: &Databases,
+    ) -> bool {
+        let address_to_entity = |address| {
+            databases
+                .address_index
+                .get(address_to_hash(address))
+                .map(|id| databases.entity_index.get(id))
+        };
+
+        let input_entities = prevouts
+            .iter()
+            .flat_map(|txout| txout.addresses.iter())
+            .filter_map(address_to_entity)
+            .collect::<HashSet<_>>();
+
+        tx.output.iter().all(|txout| {
+            !txout.addresses.is_empty()
+                && txout.addresses.iter().all(|address| {
+                    address_to_entity(address)
+                        .is_some_and(|entity| input_entities.contains(&entity))
+                })
+        })
+    }
+}
+
+/// The only output matching a predicate, among two
+fn only_match(tx: &FTransaction, predicate: impl Fn(&FTxOut) -> bool) -> Option<usize> {
+    match tx
+        .output
+        .iter()
+        .map(predicate)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [true, false] => Some(0),
+        [false, true] => Some(1),
+        _ => None,
+    }
+}
+
+impl DailyBlocksProcessor<EconomicVolume> for EconomicVolumeProcessor {
+    ///
+    /// Volume is the sum of the outputs of every non coinbase transaction,
+    /// adjusted volume what's left once self transfers and likely change are removed.
+    ///
+    /// Transactions whose outputs all go back to the sending entity are removed entirely,
+    /// otherwise change is found with the first heuristic that gives an answer:
+    /// - Address reuse: outputs going back to an input address
+    /// - Script type match: of two outputs, the only one of the same type as every input
+    /// - Round amount: of two outputs, the one that isn't round, the other being the payment
+    ///
+    /// The output spent first heuristic is deliberately missing: when a day is processed
+    /// the spend index doesn't know yet about its outputs being spent, and backfilling it
+    /// later would rewrite the adjusted volume of days already exported.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        databases: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<EconomicVolume> {
+        let mut economic_volume = EconomicVolume::default();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .filter(|tx| !tx.input.is_empty())
+            .for_each(|tx| {
+                let prevouts = self.tx_to_prevouts(tx, db, caches);
+
+                let outputs = tx.output.iter().map(|txout| txout.value).sum::<u64>();
+
+                economic_volume.volume += outputs;
+
+                let removed = &mut economic_volume.removed;
+
+                if self.is_self_transfer(tx, &prevouts, databases) {
+                    removed.self_transfer += outputs;
+
+                    return;
+                }
+
+                let input_addresses = prevouts
+                    .iter()
+                    .flat_map(|txout| txout.addresses.iter())
+                    .collect::<HashSet<_>>();
+
+                let reused = tx
+                    .output
+                    .iter()
+                    .filter(|txout| {
+                        txout
+                            .addresses
+                            .iter()
+                            .any(|address| input_addresses.contains(address))
+                    })
+                    .map(|txout| txout.value)
+                    .sum::<u64>();
+
+                let change = if reused > 0 {
+                    removed.address_reuse += reused;
+
+                    reused
+                } else if let Some(vout) = only_match(tx, |txout| {
+                    prevouts
+                        .iter()
+                        .all(|prevout| prevout.script_type == txout.script_type)
+                }) {
+                    removed.script_type_match += tx.output[vout].value;
+
+                    tx.output[vout].value
+                } else if let Some(vout) =
+                    only_match(tx, |txout| !txout.value.is_multiple_of(ROUND_AMOUNT_UNIT))
+                {
+                    removed.round_amount += tx.output[vout].value;
+
+                    tx.output[vout].value
+                } else {
+                    0
+                };
+
+                economic_volume.adjusted_volume += outputs - change;
+            });
+
+        Ok(economic_volume)
+    }
+
+    fn reads_databases(&self) -> bool {
+        true
+    }
+}
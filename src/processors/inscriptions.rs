@@ -0,0 +1,103 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{tapscript_to_inscriptions, witness_to_tapscript},
+};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct MimeTypeCounter {
+    count: usize,
+    bytes: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Inscriptions {
+    count: usize,
+    bytes: usize,
+    transactions: usize,
+    fees: u64,
+    mime_types: BTreeMap<String, MimeTypeCounter>,
+}
+
+pub type InscriptionsProcessor = Processor<Inscriptions, Inscriptions>;
+
+impl InscriptionsProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Inscriptions".to_string(),
+            output: Output::new(path, "inscriptions.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<Inscriptions> for InscriptionsProcessor {
+    ///
+    /// Looks for Ordinals envelopes in the script of every taproot script path spend.
+    ///
+    /// Fees are those of the whole transactions containing at least one inscription,
+    /// bytes only count the bodies.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<Inscriptions> {
+        let mut inscriptions = Inscriptions::default();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .for_each(|tx| {
+                let tx_inscriptions = tx
+                    .input
+                    .iter()
+                    .filter_map(|txin| {
+                        let tapscript = witness_to_tapscript(&txin.witness)?;
+
+                        let txid = txin.previous_output.txid;
+
+                        let vout = txin.previous_output.vout;
+
+                        self.outpoint_to_txout(txid, vout, db, caches)
+                            .script_pubkey
+                            .is_v1_p2tr()
+                            .then(|| tapscript_to_inscriptions(&tapscript))
+                    })
+                    .flatten()
+                    .collect::<Vec<_>>();
+
+                if tx_inscriptions.is_empty() {
+                    return;
+                }
+
+                inscriptions.transactions += 1;
+                inscriptions.fees += self.tx_to_fees(tx, db, caches);
+
+                tx_inscriptions.into_iter().for_each(|inscription| {
+                    inscriptions.count += 1;
+                    inscriptions.bytes += inscription.body_size;
+
+                    let mime_type = inscription
+                        .content_type
+                        .unwrap_or_else(|| "unknown".to_string());
+
+                    let counter = inscriptions.mime_types.entry(mime_type).or_default();
+
+                    counter.count += 1;
+                    counter.bytes += inscription.body_size;
+                });
+            });
+
+        Ok(inscriptions)
+    }
+}
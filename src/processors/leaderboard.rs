@@ -0,0 +1,107 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::tx_to_vsize,
+};
+
+/// Number of transactions kept per ranking
+const TOP_N: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TransactionRecord {
+    txid: String,
+    height: usize,
+    value: u64,
+    fee: u64,
+    fee_rate: f64,
+    inputs: usize,
+    outputs: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Leaderboard {
+    by_value: Vec<TransactionRecord>,
+    by_fee: Vec<TransactionRecord>,
+    by_fee_rate: Vec<TransactionRecord>,
+    by_inputs: Vec<TransactionRecord>,
+}
+
+pub type LeaderboardProcessor = Processor<Leaderboard, Leaderboard>;
+
+impl LeaderboardProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Leaderboard".to_string(),
+            output: Output::new(path, "leaderboard.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+fn top_by<K: PartialOrd>(
+    records: &[TransactionRecord],
+    key: impl Fn(&TransactionRecord) -> K,
+) -> Vec<TransactionRecord> {
+    let mut records = records.to_vec();
+
+    records.sort_by(|a, b| key(b).partial_cmp(&key(a)).unwrap());
+
+    records.truncate(TOP_N);
+
+    records
+}
+
+impl DailyBlocksProcessor<Leaderboard> for LeaderboardProcessor {
+    ///
+    /// Top transactions of the day, coinbases excluded, by value moved (sum of the outputs),
+    /// fee, fee rate (in sats per vbyte) and number of inputs.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<Leaderboard> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let records = blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(index, block)| {
+                block
+                    .txdata
+                    .iter()
+                    .filter(|tx| !tx.input.is_empty())
+                    .map(move |tx| (first_height + index, tx))
+            })
+            .map(|(height, tx)| {
+                let fee = self.tx_to_fees(tx, db, caches);
+
+                TransactionRecord {
+                    txid: tx.txid.to_string(),
+                    height,
+                    value: tx.output.iter().map(|txout| txout.value).sum(),
+                    fee,
+                    fee_rate: fee as f64 / tx_to_vsize(tx) as f64,
+                    inputs: tx.input.len(),
+                    outputs: tx.output.len(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Leaderboard {
+            by_value: top_by(&records, |record| record.value),
+            by_fee: top_by(&records, |record| record.fee),
+            by_fee_rate: top_by(&records, |record| record.fee_rate),
+            by_inputs: top_by(&records, |record| record.inputs),
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct LifespanBucket {
+    count: usize,
+    value: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Lifespans {
+    blocks: BTreeMap<usize, LifespanBucket>,
+    days: BTreeMap<usize, LifespanBucket>,
+    same_block: LifespanBucket,
+}
+
+pub type LifespansProcessor = Processor<Lifespans, Lifespans>;
+
+impl LifespansProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Lifespans".to_string(),
+            output: Output::new(path, "lifespans.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+/// Lower bound of the power of two bucket: 0, 1, 2, 4, 8...
+fn lifespan_to_bucket(lifespan: usize) -> usize {
+    if lifespan == 0 {
+        0
+    } else {
+        1 << lifespan.ilog2()
+    }
+}
+
+impl DailyBlocksProcessor<Lifespans> for LifespansProcessor {
+    ///
+    /// Histograms of how long spent outputs lived, in blocks and in days,
+    /// keyed by the lower bound of power of two buckets (`4` being 4 to 7).
+    ///
+    /// Same block counts outputs created and spent in the same block.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<Lifespans> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let mut lifespans = Lifespans::default();
+
+        blocks.iter().enumerate().for_each(|(index, block)| {
+            let height = first_height + index;
+
+            block
+                .txdata
+                .iter()
+                .flat_map(|tx| tx.input.iter())
+                .for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    let value = self.outpoint_to_value(txid, vout, db, caches);
+
+                    let blocks = height - self.txid_to_height(txid, db, caches);
+
+                    let days = date
+                        .signed_duration_since(self.txid_to_naive_date(txid, db, caches))
+                        .num_days() as usize;
+
+                    [
+                        lifespans
+                            .blocks
+                            .entry(lifespan_to_bucket(blocks))
+                            .or_default(),
+                        lifespans.days.entry(lifespan_to_bucket(days)).or_default(),
+                    ]
+                    .into_iter()
+                    .for_each(|bucket| {
+                        bucket.count += 1;
+                        bucket.value += value;
+                    });
+
+                    if blocks == 0 {
+                        lifespans.same_block.count += 1;
+                        lifespans.same_block.value += value;
+                    }
+                });
+        });
+
+        Ok(lifespans)
+    }
+}
@@ -0,0 +1,145 @@
+use bitcoin_explorer::{BitcoinDB, FBlock, FTransaction};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{script_to_template, txin_to_revealed_script, ScriptTemplate, SpendKind},
+};
+
+/// Upper byte of the lock time of a commitment transaction (BOLT3)
+const COMMITMENT_LOCK_TIME_TAG: u32 = 0x20;
+
+/// Upper byte of the sequence of the input of a commitment transaction (BOLT3)
+const COMMITMENT_SEQUENCE_TAG: u32 = 0x80;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Opens {
+    count: usize,
+    capacity: u64,
+}
+
+///
+/// A channel is unknown until its funding output is spent, so opens are reported
+/// on the day of the close, keyed by the day the channel was opened.
+/// Past days are never changed, the opens of a day being the sum over every later day.
+///
+#[derive(Serialize, Deserialize, Default)]
+pub struct Lightning {
+    opens_by_date: BTreeMap<String, Opens>,
+    cooperative_closes: usize,
+    force_closes: usize,
+    closed_capacity: u64,
+    mean_lifetime_days: f64,
+    mean_lifetime_blocks: f64,
+}
+
+pub type LightningProcessor = Processor<Lightning, Lightning>;
+
+impl LightningProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Lightning".to_string(),
+            output: Output::new(path, "lightning.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+/// Commitment transactions encode the obscured commitment number in the lock time and sequence
+fn is_commitment_tx(tx: &FTransaction) -> bool {
+    tx.lock_time >> 24 == COMMITMENT_LOCK_TIME_TAG
+        && tx.input.len() == 1
+        && tx.input[0].sequence >> 24 == COMMITMENT_SEQUENCE_TAG
+}
+
+impl DailyBlocksProcessor<Lightning> for LightningProcessor {
+    ///
+    /// A channel is only known once its funding output is spent with a 2-of-2 witness,
+    /// as a BOLT3 funding P2WSH multisig or a 2-of-2 tapscript.
+    ///
+    /// Everything is reported on the day of the close, opens included.
+    ///
+    /// Channels that are still open are missing: the spend index can't help as,
+    /// during a scan, it doesn't know yet about spends happening after the day processed.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<Lightning> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let mut lightning = Lightning::default();
+
+        let mut lifetimes_days = vec![];
+        let mut lifetimes_blocks = vec![];
+
+        blocks.iter().enumerate().for_each(|(index, block)| {
+            let height = first_height + index;
+
+            block.txdata.iter().for_each(|tx| {
+                tx.input.iter().for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    let prevout = self.outpoint_to_txout(txid, vout, db, caches);
+
+                    let is_channel = txin_to_revealed_script(txin, &prevout.script_pubkey)
+                        .is_some_and(|(script, kind)| match script_to_template(&script, kind) {
+                            ScriptTemplate::LightningFunding => true,
+                            ScriptTemplate::Multisig { m: 2, n: 2 } => kind == SpendKind::Tapscript,
+                            _ => false,
+                        });
+
+                    if !is_channel {
+                        return;
+                    }
+
+                    if is_commitment_tx(tx) {
+                        lightning.force_closes += 1;
+                    } else {
+                        lightning.cooperative_closes += 1;
+                    }
+
+                    lightning.closed_capacity += prevout.value;
+
+                    let open_date = self.txid_to_naive_date(txid, db, caches);
+
+                    let open_height = self.txid_to_height(txid, db, caches);
+
+                    lifetimes_days.push(date.signed_duration_since(open_date).num_days() as f64);
+                    lifetimes_blocks.push((height - open_height) as f64);
+
+                    let opens = lightning
+                        .opens_by_date
+                        .entry(open_date.to_string())
+                        .or_default();
+
+                    opens.count += 1;
+                    opens.capacity += prevout.value;
+                });
+            });
+        });
+
+        let mean = |values: &[f64]| {
+            if values.is_empty() {
+                0.0
+            } else {
+                values.iter().sum::<f64>() / values.len() as f64
+            }
+        };
+
+        lightning.mean_lifetime_days = mean(&lifetimes_days);
+        lightning.mean_lifetime_blocks = mean(&lifetimes_blocks);
+
+        Ok(lightning)
+    }
+}
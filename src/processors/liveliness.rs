@@ -0,0 +1,113 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::outpoint_to_days_destroyed,
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Liveliness {
+    supply: u64,
+    coin_days_created: f64,
+    cumulative_coin_days_created: f64,
+    coin_days_destroyed: f64,
+    cumulative_coin_days_destroyed: f64,
+    volume: u64,
+    cumulative_volume: u64,
+    liveliness: f64,
+    dormancy: f64,
+    average_spent_age: f64,
+}
+
+pub type LivelinessProcessor = Processor<Liveliness, Liveliness>;
+
+impl LivelinessProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Liveliness".to_string(),
+            output: Output::new(path, "liveliness.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<Liveliness> for LivelinessProcessor {
+    ///
+    /// Every coin held at the end of the previous day creates one coin-day,
+    /// coin-days destroyed and volume being computed like their counters.
+    ///
+    /// - Liveliness: cumulative coin-days destroyed over cumulative coin-days created
+    /// - Dormancy: coin-days destroyed over volume, the average age of the coins spent that day
+    /// - Average spent age: the same, weighted by value over every coin ever spent
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<Liveliness> {
+        let previous = self.output.get_previous(date).unwrap_or_default();
+
+        let mut coin_days_destroyed = 0.0;
+        let mut volume = 0;
+        let mut created = 0;
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .for_each(|tx| {
+                tx.input.iter().for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    coin_days_destroyed += outpoint_to_days_destroyed(txid, vout, date, db, caches);
+
+                    volume += self.outpoint_to_value(txid, vout, db, caches);
+                });
+
+                created += tx.output.iter().map(|txout| txout.value).sum::<u64>();
+            });
+
+        let coin_days_created = (previous.supply as f64) / 100_000_000.0;
+
+        let cumulative_coin_days_created =
+            previous.cumulative_coin_days_created + coin_days_created;
+
+        let cumulative_coin_days_destroyed =
+            previous.cumulative_coin_days_destroyed + coin_days_destroyed;
+
+        let cumulative_volume = previous.cumulative_volume + volume;
+
+        let ratio = |numerator: f64, denominator: f64| {
+            if denominator == 0.0 {
+                0.0
+            } else {
+                numerator / denominator
+            }
+        };
+
+        Ok(Liveliness {
+            supply: previous.supply + created - volume,
+            coin_days_created,
+            cumulative_coin_days_created,
+            coin_days_destroyed,
+            cumulative_coin_days_destroyed,
+            volume,
+            cumulative_volume,
+            liveliness: ratio(cumulative_coin_days_destroyed, cumulative_coin_days_created),
+            dormancy: ratio(coin_days_destroyed, (volume as f64) / 100_000_000.0),
+            average_spent_age: ratio(
+                cumulative_coin_days_destroyed,
+                (cumulative_volume as f64) / 100_000_000.0,
+            ),
+        })
+    }
+}
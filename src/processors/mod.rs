@@ -1,20 +1,69 @@
 use std::{cell::RefCell, collections::HashMap, marker::PhantomData};
 
-use bitcoin_explorer::{BitcoinDB, FBlock, SBlock, STransaction, Txid};
-use chrono::{Datelike, NaiveDate};
+use bitcoin_explorer::{
+    BitcoinDB, BlockHeader, FBlock, FTransaction, FTxOut, SBlock, STransaction, Txid,
+};
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::{output::Output, utils::timestamp_to_naive_date};
+use crate::{databases::Databases, output::Output, utils::timestamp_to_naive_date};
 
+pub mod active_addresses;
+pub mod active_entities;
 // pub mod addresses;
+pub mod balance_cohorts;
+pub mod block_intervals;
+pub mod coinjoins;
 // pub mod counter;
+pub mod difficulty;
+pub mod dust;
+pub mod economic_volume;
+pub mod inscriptions;
+pub mod leaderboard;
+pub mod lifespans;
+pub mod lightning;
+pub mod liveliness;
 pub mod onesteps;
+pub mod op_returns;
+pub mod precise_days_destroyed;
 // pub mod satoshis;
-
+pub mod script_templates;
+pub mod segwit_adoption;
+pub mod subsidy;
+pub mod supply;
+pub mod transaction_patterns;
+pub mod utxo_set;
+pub mod wallet_fingerprints;
+pub mod whales;
+
+pub use active_addresses::*;
+pub use active_entities::*;
 // pub use addresses::*;
+pub use balance_cohorts::*;
+pub use block_intervals::*;
+pub use coinjoins::*;
 // pub use counter::*;
+pub use difficulty::*;
+pub use dust::*;
+pub use economic_volume::*;
+pub use inscriptions::*;
+pub use leaderboard::*;
+pub use lifespans::*;
+pub use lightning::*;
+pub use liveliness::*;
 pub use onesteps::*;
+pub use op_returns::*;
+pub use precise_days_destroyed::*;
 // pub use satoshis::*;
+pub use script_templates::*;
+pub use segwit_adoption::*;
+pub use subsidy::*;
+pub use supply::*;
+pub use transaction_patterns::*;
+pub use utxo_set::*;
+pub use wallet_fingerprints::*;
+pub use whales::*;
 
 pub struct Processor<T, P> {
     name: String,
@@ -26,7 +75,9 @@ pub struct DBCaches {
     pub txid_to_transaction: RefCell<HashMap<Txid, STransaction>>,
     pub txid_to_block: RefCell<HashMap<Txid, SBlock>>,
     pub txid_to_naive_date: RefCell<HashMap<Txid, NaiveDate>>,
+    pub txid_to_height: RefCell<HashMap<Txid, usize>>,
     pub outpoint_to_value: RefCell<HashMap<(Txid, u32), u64>>,
+    pub txid_to_full_transaction: RefCell<HashMap<Txid, FTransaction>>,
 }
 
 impl DBCaches {
@@ -35,94 +86,181 @@ impl DBCaches {
             txid_to_transaction: RefCell::new(HashMap::new()),
             txid_to_block: RefCell::new(HashMap::new()),
             txid_to_naive_date: RefCell::new(HashMap::new()),
+            txid_to_height: RefCell::new(HashMap::new()),
             outpoint_to_value: RefCell::new(HashMap::new()),
+            txid_to_full_transaction: RefCell::new(HashMap::new()),
         }
     }
 
     pub fn clear(&mut self) {
-        self.txid_to_block.borrow_mut().clear();
+        self.txid_to_transaction.borrow_mut().clear();
         self.txid_to_block.borrow_mut().clear();
         self.txid_to_naive_date.borrow_mut().clear();
+        self.txid_to_height.borrow_mut().clear();
         self.outpoint_to_value.borrow_mut().clear();
+        self.txid_to_full_transaction.borrow_mut().clear();
     }
-}
-
-pub trait DailyBlocksProcessor<T> {
-    fn process_daily_blocks(
-        &self,
-        blocks: &[FBlock],
-        db: &BitcoinDB,
-        caches: &DBCaches,
-        date: &NaiveDate,
-    ) -> T;
 
-    fn txid_to_block(&self, txid: Txid, db: &BitcoinDB, caches: &DBCaches) -> SBlock {
-        if let Some(block) = caches.txid_to_block.borrow().get(&txid) {
+    pub fn txid_to_block(&self, txid: Txid, db: &BitcoinDB) -> SBlock {
+        if let Some(block) = self.txid_to_block.borrow().get(&txid) {
             return block.clone();
         }
 
-        let height = db.get_height_of_transaction(&txid).unwrap();
+        let height = self.txid_to_height(txid, db);
 
         let block = db.get_block::<SBlock>(height).unwrap();
 
-        caches
-            .txid_to_block
-            .borrow_mut()
-            .insert(txid, block.clone());
+        self.txid_to_block.borrow_mut().insert(txid, block.clone());
 
         block
     }
 
-    fn txid_to_naive_date(&self, txid: Txid, db: &BitcoinDB, caches: &DBCaches) -> NaiveDate {
-        if let Some(date) = caches.txid_to_naive_date.borrow().get(&txid) {
+    pub fn txid_to_height(&self, txid: Txid, db: &BitcoinDB) -> usize {
+        if let Some(height) = self.txid_to_height.borrow().get(&txid) {
+            return *height;
+        }
+
+        let height = db.get_height_of_transaction(&txid).unwrap();
+
+        self.txid_to_height.borrow_mut().insert(txid, height);
+
+        height
+    }
+
+    pub fn txid_to_naive_date(&self, txid: Txid, db: &BitcoinDB) -> NaiveDate {
+        if let Some(date) = self.txid_to_naive_date.borrow().get(&txid) {
             return date.to_owned();
         }
 
-        let block = self.txid_to_block(txid, db, caches);
+        let block = self.txid_to_block(txid, db);
 
         let date = timestamp_to_naive_date(block.header.time);
 
-        caches.txid_to_naive_date.borrow_mut().insert(txid, date);
+        self.txid_to_naive_date.borrow_mut().insert(txid, date);
 
         date
     }
 
-    fn txid_to_tx(&self, txid: Txid, db: &BitcoinDB, caches: &DBCaches) -> STransaction {
-        if let Some(transaction) = caches.txid_to_transaction.borrow().get(&txid) {
+    pub fn txid_to_tx(&self, txid: Txid, db: &BitcoinDB) -> STransaction {
+        if let Some(transaction) = self.txid_to_transaction.borrow().get(&txid) {
             return transaction.clone();
         }
 
         let transaction = db.get_transaction::<STransaction>(&txid).unwrap();
 
-        caches
-            .txid_to_transaction
+        self.txid_to_transaction
             .borrow_mut()
             .insert(txid, transaction.clone());
 
         transaction
     }
 
-    fn outpoint_to_value(&self, txid: Txid, vout: u32, db: &BitcoinDB, caches: &DBCaches) -> u64 {
+    pub fn outpoint_to_value(&self, txid: Txid, vout: u32, db: &BitcoinDB) -> u64 {
         let outpoint = (txid, vout);
 
-        if let Some(value) = caches.outpoint_to_value.borrow().get(&outpoint) {
+        if let Some(value) = self.outpoint_to_value.borrow().get(&outpoint) {
             return *value;
         }
 
         let value = self
-            .txid_to_tx(txid, db, caches)
+            .txid_to_tx(txid, db)
             .output
             .get(usize::try_from(vout).unwrap())
             .unwrap()
             .value;
 
-        caches
-            .outpoint_to_value
-            .borrow_mut()
-            .insert(outpoint, value);
+        self.outpoint_to_value.borrow_mut().insert(outpoint, value);
 
         value
     }
+
+    pub fn tx_to_fees(&self, tx: &FTransaction, db: &BitcoinDB) -> u64 {
+        if tx.input.is_empty() {
+            return 0;
+        }
+
+        let sent = tx
+            .input
+            .iter()
+            .map(|txin| {
+                let txid = txin.previous_output.txid;
+
+                let vout = txin.previous_output.vout;
+
+                self.outpoint_to_value(txid, vout, db)
+            })
+            .sum::<u64>();
+
+        let received = tx.output.iter().map(|txout| txout.value).sum::<u64>();
+
+        sent - received
+    }
+
+    /// Full previous output, with its script, which simple transactions don't have
+    pub fn outpoint_to_txout(&self, txid: Txid, vout: u32, db: &BitcoinDB) -> FTxOut {
+        self.txid_to_full_transaction
+            .borrow_mut()
+            .entry(txid)
+            .or_insert_with(|| db.get_transaction::<FTransaction>(&txid).unwrap())
+            .output
+            .get(usize::try_from(vout).unwrap())
+            .unwrap()
+            .clone()
+    }
+}
+
+pub trait DailyBlocksProcessor<T> {
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        databases: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<T>;
+
+    /// Whether the processor reads the databases, which must then be as of the previous day
+    fn reads_databases(&self) -> bool {
+        false
+    }
+
+    fn txid_to_naive_date(&self, txid: Txid, db: &BitcoinDB, caches: &DBCaches) -> NaiveDate {
+        caches.txid_to_naive_date(txid, db)
+    }
+
+    fn txid_to_height(&self, txid: Txid, db: &BitcoinDB, caches: &DBCaches) -> usize {
+        caches.txid_to_height(txid, db)
+    }
+
+    fn txid_to_tx(&self, txid: Txid, db: &BitcoinDB, caches: &DBCaches) -> STransaction {
+        caches.txid_to_tx(txid, db)
+    }
+
+    fn outpoint_to_value(&self, txid: Txid, vout: u32, db: &BitcoinDB, caches: &DBCaches) -> u64 {
+        caches.outpoint_to_value(txid, vout, db)
+    }
+
+    fn outpoint_to_txout(
+        &self,
+        txid: Txid,
+        vout: u32,
+        db: &BitcoinDB,
+        caches: &DBCaches,
+    ) -> FTxOut {
+        caches.outpoint_to_txout(txid, vout, db)
+    }
+
+    fn tx_to_fees(&self, tx: &FTransaction, db: &BitcoinDB, caches: &DBCaches) -> u64 {
+        caches.tx_to_fees(tx, db)
+    }
+
+    fn block_to_height(&self, block: &FBlock, db: &BitcoinDB) -> usize {
+        db.get_height_from_hash(&block.header.block_hash).unwrap()
+    }
+
+    fn height_to_header(&self, height: usize, db: &BitcoinDB) -> BlockHeader {
+        db.get_header(height).unwrap().block_header
+    }
 }
 
 pub trait DailyBlocksImporter {
@@ -132,6 +270,7 @@ pub trait DailyBlocksImporter {
         blocks: &[FBlock],
         db: &BitcoinDB,
         caches: &DBCaches,
+        databases: &Databases,
     ) -> color_eyre::Result<()>;
 }
 
@@ -146,25 +285,31 @@ where
         blocks: &[FBlock],
         db: &BitcoinDB,
         caches: &DBCaches,
+        databases: &Databases,
     ) -> color_eyre::Result<()> {
-        if self.output.data.borrow().get(&date.to_string()).is_none() {
-            println!("Processing {}", self.name);
+        if self.output.data.borrow().get(&date.to_string()).is_some() {
+            println!("Skipping {}", self.name);
 
-            self.output.data.borrow_mut().insert(
-                date.to_string(),
-                self.process_daily_blocks(blocks, db, caches, &date),
-            );
+            return Ok(());
+        }
 
-            let day = date.day();
+        // Processors reading the databases expect them as of the end of the previous day
+        if self.reads_databases() && databases.is_date_imported(&date) {
+            return Err(eyre!(
+                "{} has no output for {date} but the databases already imported it, \
+                delete ./databases and every output to rebuild them together",
+                self.name
+            ));
+        }
 
-            if day == 1 || day == 14 {
-                println!("Saving {}", self.name);
+        println!("Processing {}", self.name);
 
-                self.export_output()?;
-            }
-        } else {
-            println!("Skipping {}", self.name);
-        }
+        let value = self.process_daily_blocks(blocks, db, caches, databases, &date)?;
+
+        self.output
+            .data
+            .borrow_mut()
+            .insert(date.to_string(), value);
 
         Ok(())
     }
@@ -3,6 +3,7 @@ use chrono::NaiveDate;
 use std::marker::PhantomData;
 
 use crate::{
+    databases::Databases,
     output::*,
     processors::{DBCaches, DailyBlocksProcessor, Processor},
 };
@@ -27,9 +28,10 @@ impl DailyBlocksProcessor<u64> for CoinbasesCounterProcessor {
         blocks: &[FBlock],
         _: &BitcoinDB,
         _: &DBCaches,
+        _: &Databases,
         _: &NaiveDate,
-    ) -> u64 {
-        blocks
+    ) -> color_eyre::Result<u64> {
+        Ok(blocks
             .iter()
             .flat_map(|block| {
                 block
@@ -42,6 +44,6 @@ impl DailyBlocksProcessor<u64> for CoinbasesCounterProcessor {
                         recieved
                     })
             })
-            .sum::<u64>()
+            .sum::<u64>())
     }
 }
@@ -3,8 +3,10 @@ use chrono::NaiveDate;
 use std::marker::PhantomData;
 
 use crate::{
+    databases::Databases,
     output::*,
     processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::outpoint_to_days_destroyed,
 };
 
 pub struct DaysDestroyedCounter;
@@ -27,9 +29,10 @@ impl DailyBlocksProcessor<f64> for DaysDestroyedCounterProcessor {
         blocks: &[FBlock],
         db: &BitcoinDB,
         caches: &DBCaches,
+        _: &Databases,
         date: &NaiveDate,
-    ) -> f64 {
-        blocks
+    ) -> color_eyre::Result<f64> {
+        Ok(blocks
             .iter()
             .flat_map(|block| {
                 block.txdata.iter().flat_map(|tx| {
@@ -38,18 +41,10 @@ impl DailyBlocksProcessor<f64> for DaysDestroyedCounterProcessor {
 
                         let vout = txin.previous_output.vout;
 
-                        let value = self.outpoint_to_value(txid, vout, db, caches);
-
-                        let bitcoins = (value as f64) / 100_000_000.0;
-
-                        let prev_date = self.txid_to_naive_date(txid, db, caches);
-
-                        let num_days = date.signed_duration_since(prev_date).num_days() as f64;
-
-                        bitcoins * num_days
+                        outpoint_to_days_destroyed(txid, vout, date, db, caches)
                     })
                 })
             })
-            .sum()
+            .sum())
     }
 }
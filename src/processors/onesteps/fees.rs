@@ -3,6 +3,7 @@ use chrono::NaiveDate;
 use std::marker::PhantomData;
 
 use crate::{
+    databases::Databases,
     output::*,
     processors::{DBCaches, DailyBlocksProcessor, Processor},
 };
@@ -27,33 +28,13 @@ impl DailyBlocksProcessor<u64> for FeesCounterProcessor {
         blocks: &[FBlock],
         db: &BitcoinDB,
         caches: &DBCaches,
+        _: &Databases,
         _: &NaiveDate,
-    ) -> u64 {
-        blocks
+    ) -> color_eyre::Result<u64> {
+        Ok(blocks
             .iter()
-            .flat_map(|block| {
-                block
-                    .txdata
-                    .iter()
-                    .filter(|tx| !tx.input.is_empty())
-                    .map(|tx| {
-                        let sent = tx
-                            .input
-                            .iter()
-                            .map(|txin| {
-                                let txid = txin.previous_output.txid;
-
-                                let vout = txin.previous_output.vout;
-
-                                self.outpoint_to_value(txid, vout, db, caches)
-                            })
-                            .sum::<u64>();
-
-                        let recieved = tx.output.iter().map(|txout| txout.value).sum::<u64>();
-
-                        sent - recieved
-                    })
-            })
-            .sum::<u64>()
+            .flat_map(|block| block.txdata.iter())
+            .map(|tx| caches.tx_to_fees(tx, db))
+            .sum::<u64>())
     }
 }
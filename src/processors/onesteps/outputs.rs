@@ -3,6 +3,7 @@ use chrono::NaiveDate;
 use std::marker::PhantomData;
 
 use crate::{
+    databases::Databases,
     output::*,
     processors::{DBCaches, DailyBlocksProcessor, Processor},
 };
@@ -27,11 +28,12 @@ impl DailyBlocksProcessor<usize> for OutputsCounterProcessor {
         blocks: &[FBlock],
         _: &BitcoinDB,
         _: &DBCaches,
+        _: &Databases,
         _: &NaiveDate,
-    ) -> usize {
-        blocks
+    ) -> color_eyre::Result<usize> {
+        Ok(blocks
             .iter()
             .flat_map(|block| block.txdata.iter().map(|tx| tx.output.len()))
-            .sum::<usize>()
+            .sum::<usize>())
     }
 }
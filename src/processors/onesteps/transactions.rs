@@ -3,6 +3,7 @@ use chrono::NaiveDate;
 use std::marker::PhantomData;
 
 use crate::{
+    databases::Databases,
     output::*,
     processors::{DBCaches, DailyBlocksProcessor, Processor},
 };
@@ -27,8 +28,9 @@ impl DailyBlocksProcessor<usize> for TransactionsCounterProcessor {
         blocks: &[FBlock],
         _: &BitcoinDB,
         _: &DBCaches,
+        _: &Databases,
         _: &NaiveDate,
-    ) -> usize {
-        blocks.iter().map(|block| block.txdata.len()).sum()
+    ) -> color_eyre::Result<usize> {
+        Ok(blocks.iter().map(|block| block.txdata.len()).sum())
     }
 }
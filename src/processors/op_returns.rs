@@ -0,0 +1,190 @@
+use bitcoin::{
+    blockdata::{opcodes::all, script::Instruction},
+    hashes::Hash,
+};
+use bitcoin_explorer::{
+    parser::script::ScriptType, BitcoinDB, FBlock, FTransaction, Script, ToHex,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+const OMNI_PREFIX: &[u8] = b"omni";
+
+const COUNTERPARTY_PREFIX: &[u8] = b"CNTRPRTY";
+
+const STACKS_PREFIXES: [&[u8]; 2] = [b"X2", b"id"];
+
+const RSK_PREFIX: &[u8] = b"RSKBLOCK:";
+
+const WITNESS_COMMITMENT_PREFIX: &[u8] = &[0xaa, 0x21, 0xa9, 0xed];
+
+const MAX_UNKNOWN_SAMPLES: usize = 10;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ProtocolCounter {
+    count: usize,
+    bytes: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct OpReturns {
+    protocols: BTreeMap<String, ProtocolCounter>,
+    sizes: BTreeMap<usize, usize>,
+    unknown_samples: Vec<String>,
+}
+
+pub type OpReturnsProcessor = Processor<OpReturns, OpReturns>;
+
+impl OpReturnsProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "OpReturns".to_string(),
+            output: Output::new(path, "op_returns.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+/// Pushed data following `OP_RETURN`, and whether it starts with `OP_13` (Runes)
+fn script_to_payload(script: &Script) -> (Vec<u8>, bool) {
+    let rest = Script::from(script.as_bytes()[1..].to_vec());
+
+    let instructions = rest
+        .instructions()
+        .map_while(Result::ok)
+        .collect::<Vec<_>>();
+
+    let is_runestone = instructions.first() == Some(&Instruction::Op(all::OP_PUSHNUM_13));
+
+    let payload = instructions
+        .into_iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::PushBytes(bytes) => Some(bytes.to_vec()),
+            Instruction::Op(_) => None,
+        })
+        .flatten()
+        .collect();
+
+    (payload, is_runestone)
+}
+
+/// Counterparty data is obfuscated with ARC4, keyed by the txid of the first input
+fn is_counterparty(tx: &FTransaction, payload: &[u8]) -> bool {
+    let Some(txin) = tx.input.first() else {
+        return false;
+    };
+
+    let mut key = txin.previous_output.txid.into_inner();
+
+    key.reverse();
+
+    arc4(
+        &key,
+        &payload[..payload.len().min(COUNTERPARTY_PREFIX.len())],
+    ) == COUNTERPARTY_PREFIX
+}
+
+fn arc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state = (0..=255).collect::<Vec<u8>>();
+
+    let mut j: u8 = 0;
+
+    (0..256).for_each(|i| {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, j as usize);
+    });
+
+    let (mut i, mut j) = (0_u8, 0_u8);
+
+    data.iter()
+        .map(|byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[i as usize]);
+            state.swap(i as usize, j as usize);
+
+            byte ^ state[state[i as usize].wrapping_add(state[j as usize]) as usize]
+        })
+        .collect()
+}
+
+fn payload_to_protocol(tx: &FTransaction, payload: &[u8], is_runestone: bool) -> &'static str {
+    if is_runestone {
+        "runes"
+    } else if payload.starts_with(WITNESS_COMMITMENT_PREFIX) {
+        "witness_commitment"
+    } else if payload.starts_with(RSK_PREFIX) {
+        "rsk"
+    } else if payload.starts_with(OMNI_PREFIX) {
+        "omni"
+    } else if STACKS_PREFIXES
+        .iter()
+        .any(|prefix| payload.starts_with(prefix))
+    {
+        "stacks"
+    } else if is_counterparty(tx, payload) {
+        "counterparty"
+    } else {
+        "unknown"
+    }
+}
+
+impl DailyBlocksProcessor<OpReturns> for OpReturnsProcessor {
+    ///
+    /// Classifies every OP_RETURN output by the protocol its payload belongs to,
+    /// sizes being the number of outputs per payload size in bytes.
+    ///
+    /// The first unknown payloads of the day are kept as hex samples.
+    ///
+    /// VeriBlock payloads have no prefix or magic to recognize them by, only a usual size
+    /// shared with plenty of other payloads, so they can't be told apart and end up in unknown.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        _: &BitcoinDB,
+        _: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<OpReturns> {
+        let mut op_returns = OpReturns::default();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .for_each(|tx| {
+                tx.output
+                    .iter()
+                    .filter(|txout| txout.script_type == ScriptType::OpReturn)
+                    .for_each(|txout| {
+                        let (payload, is_runestone) = script_to_payload(&txout.script_pubkey);
+
+                        let protocol = payload_to_protocol(tx, &payload, is_runestone);
+
+                        let counter = op_returns
+                            .protocols
+                            .entry(protocol.to_string())
+                            .or_default();
+
+                        counter.count += 1;
+                        counter.bytes += payload.len();
+
+                        *op_returns.sizes.entry(payload.len()).or_default() += 1;
+
+                        if protocol == "unknown"
+                            && op_returns.unknown_samples.len() < MAX_UNKNOWN_SAMPLES
+                        {
+                            op_returns.unknown_samples.push(payload.to_hex());
+                        }
+                    });
+            });
+
+        Ok(op_returns)
+    }
+}
@@ -0,0 +1,81 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PreciseDaysDestroyed {
+    coin_blocks_destroyed: f64,
+    coin_days_destroyed: f64,
+}
+
+pub type PreciseDaysDestroyedProcessor = Processor<PreciseDaysDestroyed, PreciseDaysDestroyed>;
+
+impl PreciseDaysDestroyedProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "PreciseDaysDestroyed".to_string(),
+            output: Output::new(path, "precise_days_destroyed.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<PreciseDaysDestroyed> for PreciseDaysDestroyedProcessor {
+    ///
+    /// Unlike the days destroyed counter, which counts whole days between two dates,
+    /// ages are the difference of heights, and of block timestamps in fractions of a day.
+    ///
+    /// Block timestamps aren't strictly increasing, so a coin spent soon after its creation
+    /// can destroy slightly negative coin-days.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<PreciseDaysDestroyed> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let mut destroyed = PreciseDaysDestroyed::default();
+
+        blocks.iter().enumerate().for_each(|(index, block)| {
+            let height = first_height + index;
+
+            block
+                .txdata
+                .iter()
+                .flat_map(|tx| tx.input.iter())
+                .for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    let bitcoins =
+                        (self.outpoint_to_value(txid, vout, db, caches) as f64) / 100_000_000.0;
+
+                    let prev_height = self.txid_to_height(txid, db, caches);
+
+                    let num_blocks = (height - prev_height) as f64;
+
+                    let num_seconds = block.header.time as f64
+                        - self.height_to_header(prev_height, db).time as f64;
+
+                    destroyed.coin_blocks_destroyed += bitcoins * num_blocks;
+                    destroyed.coin_days_destroyed += bitcoins * num_seconds / SECONDS_PER_DAY;
+                });
+        });
+
+        Ok(destroyed)
+    }
+}
@@ -0,0 +1,94 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{script_to_template, script_to_timelocks, txin_to_revealed_script},
+};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct TemplateCounter {
+    count: usize,
+    value: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ScriptTemplates {
+    templates: BTreeMap<String, TemplateCounter>,
+    cltv: TemplateCounter,
+    csv: TemplateCounter,
+}
+
+pub type ScriptTemplatesProcessor = Processor<ScriptTemplates, ScriptTemplates>;
+
+impl ScriptTemplatesProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "ScriptTemplates".to_string(),
+            output: Output::new(path, "script_templates.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<ScriptTemplates> for ScriptTemplatesProcessor {
+    ///
+    /// Classifies the scripts revealed by P2SH, P2WSH and tapscript spends,
+    /// the value being the one of the outputs spent.
+    ///
+    /// CLTV and CSV count every script using them, whatever its template.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<ScriptTemplates> {
+        let mut script_templates = ScriptTemplates::default();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .flat_map(|tx| tx.input.iter())
+            .for_each(|txin| {
+                let txid = txin.previous_output.txid;
+
+                let vout = txin.previous_output.vout;
+
+                let prevout = self.outpoint_to_txout(txid, vout, db, caches);
+
+                let Some((script, kind)) = txin_to_revealed_script(txin, &prevout.script_pubkey)
+                else {
+                    return;
+                };
+
+                let counter = script_templates
+                    .templates
+                    .entry(script_to_template(&script, kind).name())
+                    .or_default();
+
+                counter.count += 1;
+                counter.value += prevout.value;
+
+                let (cltv, csv) = script_to_timelocks(&script);
+
+                if cltv {
+                    script_templates.cltv.count += 1;
+                    script_templates.cltv.value += prevout.value;
+                }
+
+                if csv {
+                    script_templates.csv.count += 1;
+                    script_templates.csv.value += prevout.value;
+                }
+            });
+
+        Ok(script_templates)
+    }
+}
@@ -0,0 +1,158 @@
+use bitcoin::TxIn;
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::witness_to_elements,
+};
+
+/// Weight of the segwit marker and flag, which a legacy transaction doesn't have
+const SEGWIT_MARKER_AND_FLAG_WEIGHT: usize = 2;
+
+/// Weight of the empty stack count that inputs without a witness still carry
+const EMPTY_WITNESS_WEIGHT: usize = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct SegwitAdoption {
+    transactions: usize,
+    witness_share: f64,
+    native_segwit_only_share: f64,
+    p2sh_segwit_share: f64,
+    taproot_key_path_share: f64,
+    taproot_script_path_share: f64,
+    weight_saved: usize,
+}
+
+pub type SegwitAdoptionProcessor = Processor<SegwitAdoption, SegwitAdoption>;
+
+impl SegwitAdoptionProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "SegwitAdoption".to_string(),
+            output: Output::new(path, "segwit_adoption.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<SegwitAdoption> for SegwitAdoptionProcessor {
+    ///
+    /// Shares of non coinbase transactions spending:
+    /// - At least one witness input
+    /// - Only native segwit inputs (v0 and taproot)
+    /// - At least one P2SH wrapped segwit input
+    /// - At least one taproot input through the key path, or the script path
+    ///
+    /// Weight saved is what the witness data would have weighted in a legacy transaction,
+    /// at 4 weight units per byte instead of 1, minus the segwit marker and flag
+    /// and the empty witnesses of the other inputs.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<SegwitAdoption> {
+        let mut transactions = 0;
+        let mut witness = 0;
+        let mut native_segwit_only = 0;
+        let mut p2sh_segwit = 0;
+        let mut taproot_key_path = 0;
+        let mut taproot_script_path = 0;
+        let mut weight_saved = 0;
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .filter(|tx| !tx.input.is_empty())
+            .for_each(|tx| {
+                transactions += 1;
+
+                let witness_inputs = tx
+                    .input
+                    .iter()
+                    .filter(|txin| !txin.witness.is_empty())
+                    .collect::<Vec<_>>();
+
+                if witness_inputs.is_empty() {
+                    return;
+                }
+
+                witness += 1;
+
+                let (native_inputs, wrapped_inputs): (Vec<_>, Vec<_>) = witness_inputs
+                    .into_iter()
+                    .partition(|txin| txin.script_sig.is_empty());
+
+                if wrapped_inputs.is_empty() && native_inputs.len() == tx.input.len() {
+                    native_segwit_only += 1;
+                }
+
+                if !wrapped_inputs.is_empty() {
+                    p2sh_segwit += 1;
+                }
+
+                let taproot_inputs = native_inputs
+                    .into_iter()
+                    .filter(|txin| {
+                        let txid = txin.previous_output.txid;
+
+                        let vout = txin.previous_output.vout;
+
+                        self.outpoint_to_txout(txid, vout, db, caches)
+                            .script_pubkey
+                            .is_v1_p2tr()
+                    })
+                    .collect::<Vec<_>>();
+
+                let is_key_path = |txin: &&TxIn| witness_to_elements(&txin.witness).len() == 1;
+
+                if taproot_inputs.iter().any(is_key_path) {
+                    taproot_key_path += 1;
+                }
+
+                if !taproot_inputs.iter().all(is_key_path) {
+                    taproot_script_path += 1;
+                }
+
+                let (witness_size, empty_witnesses) =
+                    tx.input.iter().fold((0, 0), |(size, empty), txin| {
+                        if txin.witness.is_empty() {
+                            (size, empty + 1)
+                        } else {
+                            (size + txin.witness.serialized_len(), empty)
+                        }
+                    });
+
+                let overhead =
+                    SEGWIT_MARKER_AND_FLAG_WEIGHT + empty_witnesses * EMPTY_WITNESS_WEIGHT;
+
+                weight_saved += (witness_size * 3).saturating_sub(overhead);
+            });
+
+        let share = |count: usize| {
+            if transactions == 0 {
+                0.0
+            } else {
+                count as f64 / transactions as f64
+            }
+        };
+
+        Ok(SegwitAdoption {
+            transactions,
+            witness_share: share(witness),
+            native_segwit_only_share: share(native_segwit_only),
+            p2sh_segwit_share: share(p2sh_segwit),
+            taproot_key_path_share: share(taproot_key_path),
+            taproot_script_path_share: share(taproot_script_path),
+            weight_saved,
+        })
+    }
+}
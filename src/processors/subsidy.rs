@@ -0,0 +1,101 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{block_to_claim, height_to_theoretical_supply},
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Subsidy {
+    expected: u64,
+    issued: u64,
+    fees: u64,
+    claimed: u64,
+    under_claimed: u64,
+    issued_supply: u64,
+    theoretical_supply: u64,
+    over_claims: Vec<OverClaim>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OverClaim {
+    height: usize,
+    claimed: u64,
+    allowed: u64,
+}
+
+pub type SubsidyProcessor = Processor<Subsidy, Subsidy>;
+
+impl SubsidyProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Subsidy".to_string(),
+            output: Output::new(path, "subsidy.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<Subsidy> for SubsidyProcessor {
+    ///
+    /// Compares what each coinbase claimed with what it was allowed to claim
+    /// (subsidy from the halving schedule + fees of the block).
+    ///
+    /// Under-claimed amounts are lost forever,
+    /// over-claims should never happen and are reported as alerts.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<Subsidy> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let issued_supply = self
+            .output
+            .get_previous(date)
+            .map_or(0, |previous| previous.issued_supply);
+
+        let mut subsidy = Subsidy {
+            expected: 0,
+            issued: 0,
+            fees: 0,
+            claimed: 0,
+            under_claimed: 0,
+            issued_supply,
+            theoretical_supply: height_to_theoretical_supply(first_height + blocks.len() - 1),
+            over_claims: vec![],
+        };
+
+        blocks.iter().enumerate().for_each(|(index, block)| {
+            let height = first_height + index;
+
+            let claim = block_to_claim(block, height, db, caches);
+
+            if claim.claimed > claim.allowed() {
+                subsidy.over_claims.push(OverClaim {
+                    height,
+                    claimed: claim.claimed,
+                    allowed: claim.allowed(),
+                });
+            }
+
+            subsidy.expected += claim.subsidy;
+            subsidy.issued += claim.issued();
+            subsidy.fees += claim.fees;
+            subsidy.claimed += claim.claimed;
+            subsidy.under_claimed += claim.under_claimed();
+            subsidy.issued_supply += claim.issued();
+        });
+
+        Ok(subsidy)
+    }
+}
@@ -0,0 +1,113 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{
+        block_to_claim, height_to_theoretical_supply, is_unspendable,
+        BIP30_DUPLICATE_COINBASE_HEIGHTS,
+    },
+};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Unspendable {
+    op_return: u64,
+    genesis: u64,
+    bip30: u64,
+    under_claimed: u64,
+}
+
+impl Unspendable {
+    fn total(&self) -> u64 {
+        self.op_return + self.genesis + self.bip30 + self.under_claimed
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Supply {
+    circulating: u64,
+    unspendable: Unspendable,
+    cumulative_unspendable: Unspendable,
+}
+
+pub type SupplyProcessor = Processor<Supply, Supply>;
+
+impl SupplyProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Supply".to_string(),
+            output: Output::new(path, "supply.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl DailyBlocksProcessor<Supply> for SupplyProcessor {
+    ///
+    /// Circulating supply is the theoretical supply minus everything that is provably unspendable:
+    /// - Valued OP_RETURN and oversized outputs, which Bitcoin Core never adds to its UTXO set
+    /// - The genesis coinbase, which isn't part of the UTXO set
+    /// - Coinbases overwritten by a duplicate txid (BIP30)
+    /// - Subsidy and fees that miners didn't claim
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<Supply> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let mut unspendable = Unspendable::default();
+
+        blocks.iter().enumerate().for_each(|(index, block)| {
+            let height = first_height + index;
+
+            let claim = block_to_claim(block, height, db, caches);
+
+            if height == 0 {
+                unspendable.genesis += claim.claimed;
+            }
+
+            if BIP30_DUPLICATE_COINBASE_HEIGHTS.contains(&height) {
+                unspendable.bip30 += claim.claimed;
+            }
+
+            unspendable.under_claimed += claim.under_claimed();
+
+            unspendable.op_return += block
+                .txdata
+                .iter()
+                .flat_map(|tx| tx.output.iter())
+                .filter(|txout| is_unspendable(&txout.script_pubkey))
+                .map(|txout| txout.value)
+                .sum::<u64>();
+        });
+
+        let mut cumulative_unspendable = self
+            .output
+            .get_previous(date)
+            .map_or_else(Unspendable::default, |previous| {
+                previous.cumulative_unspendable
+            });
+
+        cumulative_unspendable.op_return += unspendable.op_return;
+        cumulative_unspendable.genesis += unspendable.genesis;
+        cumulative_unspendable.bip30 += unspendable.bip30;
+        cumulative_unspendable.under_claimed += unspendable.under_claimed;
+
+        let theoretical_supply = height_to_theoretical_supply(first_height + blocks.len() - 1);
+
+        Ok(Supply {
+            circulating: theoretical_supply - cumulative_unspendable.total(),
+            unspendable,
+            cumulative_unspendable,
+        })
+    }
+}
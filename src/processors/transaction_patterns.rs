@@ -0,0 +1,140 @@
+use bitcoin_explorer::{BitcoinDB, FBlock, FTransaction};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::is_coinjoin_like,
+};
+
+/// Minimum share of the value kept by the biggest output for a transaction to be a peel chain step
+const PEEL_CHAIN_MIN_REMAINDER_RATIO: f64 = 0.9;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PatternCounter {
+    count: usize,
+    value: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct TransactionPatterns {
+    simple_payment: PatternCounter,
+    consolidation: PatternCounter,
+    batch_payout: PatternCounter,
+    self_transfer: PatternCounter,
+    coinjoin_like: PatternCounter,
+    peel_chain_step: PatternCounter,
+    other: PatternCounter,
+}
+
+pub type TransactionPatternsProcessor = Processor<TransactionPatterns, TransactionPatterns>;
+
+impl TransactionPatternsProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "TransactionPatterns".to_string(),
+            output: Output::new(path, "transaction_patterns.json"),
+            pd: PhantomData,
+        }
+    }
+
+    fn is_self_transfer(&self, tx: &FTransaction, db: &BitcoinDB, caches: &DBCaches) -> bool {
+        let input_addresses = tx
+            .input
+            .iter()
+            .flat_map(|txin| {
+                let txid = txin.previous_output.txid;
+
+                let vout = txin.previous_output.vout;
+
+                let txprev = self.txid_to_tx(txid, db, caches);
+
+                let txout = txprev.output.get(vout as usize).unwrap();
+
+                txout.addresses.to_vec()
+            })
+            .collect::<HashSet<_>>();
+
+        tx.output.iter().all(|txout| {
+            !txout.addresses.is_empty()
+                && txout
+                    .addresses
+                    .iter()
+                    .all(|address| input_addresses.contains(address))
+        })
+    }
+
+    /// One input coming from another 1-in-2-out transaction, with most of the value in one output
+    fn is_peel_chain_step(&self, tx: &FTransaction, db: &BitcoinDB, caches: &DBCaches) -> bool {
+        let total = tx.output.iter().map(|txout| txout.value).sum::<u64>();
+
+        let remainder = tx.output.iter().map(|txout| txout.value).max().unwrap_or(0);
+
+        if total == 0 || (remainder as f64) < total as f64 * PEEL_CHAIN_MIN_REMAINDER_RATIO {
+            return false;
+        }
+
+        let txprev = self.txid_to_tx(tx.input[0].previous_output.txid, db, caches);
+
+        txprev.input.len() == 1 && txprev.output.len() == 2
+    }
+}
+
+impl DailyBlocksProcessor<TransactionPatterns> for TransactionPatternsProcessor {
+    ///
+    /// Each non coinbase transaction falls into the first pattern it matches:
+    /// - CoinJoin-like: several inputs and several outputs of equal value
+    /// - Self transfer: every output goes back to an input address
+    /// - Consolidation: many inputs, one output
+    /// - Batch payout: one input, three outputs or more
+    /// - Peel chain step: one input, two outputs, continuing a 1-in-2-out chain
+    /// - Simple payment: any other one input, two outputs
+    /// - Other
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<TransactionPatterns> {
+        let mut patterns = TransactionPatterns::default();
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .filter(|tx| !tx.input.is_empty())
+            .for_each(|tx| {
+                let inputs = tx.input.len();
+
+                let outputs = tx.output.len();
+
+                let counter = if is_coinjoin_like(tx) {
+                    &mut patterns.coinjoin_like
+                } else if self.is_self_transfer(tx, db, caches) {
+                    &mut patterns.self_transfer
+                } else if inputs > 1 && outputs == 1 {
+                    &mut patterns.consolidation
+                } else if inputs == 1 && outputs > 2 {
+                    &mut patterns.batch_payout
+                } else if inputs == 1 && outputs == 2 {
+                    if self.is_peel_chain_step(tx, db, caches) {
+                        &mut patterns.peel_chain_step
+                    } else {
+                        &mut patterns.simple_payment
+                    }
+                } else {
+                    &mut patterns.other
+                };
+
+                counter.count += 1;
+                counter.value += tx.output.iter().map(|txout| txout.value).sum::<u64>();
+            });
+
+        Ok(patterns)
+    }
+}
@@ -0,0 +1,241 @@
+use bitcoin_explorer::{BitcoinDB, FBlock, FTxOut};
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+    path::Path,
+};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{is_unspendable, script_to_bogosize, Snapshot, BIP30_DUPLICATE_COINBASE_HEIGHTS},
+};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct UtxoSet {
+    height: usize,
+    count: u64,
+    value: u64,
+    bogosize: u64,
+    script_types: BTreeMap<String, u64>,
+}
+
+/// Type of the output, with taproot which bitcoin-explorer only knows as a witness program
+fn txout_to_script_type(txout: &FTxOut) -> String {
+    if txout.script_pubkey.is_v1_p2tr() {
+        "Pay2Taproot".to_string()
+    } else {
+        format!("{:?}", txout.script_type)
+    }
+}
+
+impl UtxoSet {
+    fn add(&mut self, txout: &FTxOut) {
+        self.count += 1;
+        self.value += txout.value;
+        self.bogosize += script_to_bogosize(&txout.script_pubkey);
+
+        *self
+            .script_types
+            .entry(txout_to_script_type(txout))
+            .or_default() += 1;
+    }
+
+    /// Fails instead of wrapping when the set and the chain disagree
+    fn remove(&mut self, txout: &FTxOut, height: usize) -> color_eyre::Result<()> {
+        let script_type = txout_to_script_type(txout);
+
+        let missing = || eyre!("A {script_type} output spent at height {height} isn't in the set");
+
+        self.count = self.count.checked_sub(1).ok_or_else(missing)?;
+        self.value = self.value.checked_sub(txout.value).ok_or_else(missing)?;
+        self.bogosize = self
+            .bogosize
+            .checked_sub(script_to_bogosize(&txout.script_pubkey))
+            .ok_or_else(missing)?;
+
+        let count = self
+            .script_types
+            .get_mut(&script_type)
+            .ok_or_else(missing)?;
+
+        *count = count.checked_sub(1).ok_or_else(missing)?;
+
+        Ok(())
+    }
+
+    /// Differences with the expected set, one line per total that doesn't match
+    fn diff(&self, expected: &Self) -> Vec<String> {
+        let mut mismatches = [
+            ("count", self.count, expected.count),
+            ("value", self.value, expected.value),
+            ("bogosize", self.bogosize, expected.bogosize),
+        ]
+        .into_iter()
+        .filter(|(_, value, expected)| value != expected)
+        .map(|(name, value, expected)| format!("{name}: {value} instead of {expected}"))
+        .collect::<Vec<_>>();
+
+        self.script_types
+            .keys()
+            .chain(expected.script_types.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .for_each(|script_type| {
+                let value = self.script_types.get(script_type).copied().unwrap_or(0);
+
+                let expected = expected.script_types.get(script_type).copied().unwrap_or(0);
+
+                if value != expected {
+                    mismatches.push(format!("{script_type}: {value} instead of {expected}"));
+                }
+            });
+
+        mismatches
+    }
+}
+
+pub type UtxoSetProcessor = Processor<UtxoSet, UtxoSet>;
+
+impl UtxoSetProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "UtxoSet".to_string(),
+            output: Output::new(path, "utxo_set.json"),
+            pd: PhantomData,
+        }
+    }
+
+    fn apply_block(
+        &self,
+        utxo_set: &mut UtxoSet,
+        block: &FBlock,
+        height: usize,
+        db: &BitcoinDB,
+        caches: &DBCaches,
+    ) -> color_eyre::Result<()> {
+        block
+            .txdata
+            .iter()
+            .try_for_each(|tx| -> color_eyre::Result<()> {
+                tx.input.iter().try_for_each(|txin| {
+                    let txid = txin.previous_output.txid;
+
+                    let vout = txin.previous_output.vout;
+
+                    utxo_set.remove(&self.outpoint_to_txout(txid, vout, db, caches), height)
+                })?;
+
+                let is_excluded_coinbase = tx.input.is_empty()
+                    && (height == 0 || BIP30_DUPLICATE_COINBASE_HEIGHTS.contains(&height));
+
+                if !is_excluded_coinbase {
+                    tx.output
+                        .iter()
+                        .filter(|txout| !is_unspendable(&txout.script_pubkey))
+                        .for_each(|txout| utxo_set.add(txout));
+                }
+
+                Ok(())
+            })?;
+
+        utxo_set.height = height;
+
+        Ok(())
+    }
+
+    ///
+    /// Compares the set at the height of a `dumptxoutset` snapshot with the snapshot itself,
+    /// starting from the closest day already processed and applying the blocks left.
+    ///
+    /// Returns an error listing the totals that don't match.
+    ///
+    pub fn verify_snapshot(&self, path: &Path, db: &BitcoinDB) -> color_eyre::Result<()> {
+        let snapshot = Snapshot::open(path)?;
+
+        let height = db
+            .get_height_from_hash(&snapshot.block_hash)
+            .map_err(|_| eyre!("Snapshot block {} not found", snapshot.block_hash))?;
+
+        println!(
+            "Verifying {} coins at height {height}...",
+            snapshot.coins_count
+        );
+
+        let mut utxo_set = self
+            .output
+            .data
+            .borrow()
+            .values()
+            .filter(|utxo_set| utxo_set.height <= height)
+            .max_by_key(|utxo_set| utxo_set.height)
+            .cloned()
+            .ok_or_else(|| eyre!("No day processed before height {height}"))?;
+
+        let caches = DBCaches::new();
+
+        db.iter_block::<FBlock>(utxo_set.height + 1, height + 1)
+            .zip(utxo_set.height + 1..)
+            .try_for_each(|(block, height)| {
+                self.apply_block(&mut utxo_set, &block, height, db, &caches)
+            })?;
+
+        let mut expected = UtxoSet {
+            height,
+            ..Default::default()
+        };
+
+        snapshot.for_each_txout(|txout| expected.add(&FTxOut::from(txout)))?;
+
+        let mismatches = utxo_set.diff(&expected);
+
+        if mismatches.is_empty() {
+            println!("Every total matches");
+
+            return Ok(());
+        }
+
+        mismatches
+            .iter()
+            .for_each(|mismatch| println!("{mismatch}"));
+
+        Err(eyre!(
+            "{} totals don't match the snapshot",
+            mismatches.len()
+        ))
+    }
+}
+
+impl DailyBlocksProcessor<UtxoSet> for UtxoSetProcessor {
+    ///
+    /// Running UTXO set as of the end of the day, following `gettxoutsetinfo`:
+    /// - Outputs starting with OP_RETURN or with a script over 10,000 bytes are never added
+    /// - The genesis coinbase isn't part of it
+    /// - Coinbases overwritten by a duplicate txid (BIP30) are only counted once
+    ///
+    /// Height is the one of the last block of the day,
+    /// bogosize the estimated serialized size of the set, as computed by Bitcoin Core.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        date: &NaiveDate,
+    ) -> color_eyre::Result<UtxoSet> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let mut utxo_set = self.output.get_previous(date).unwrap_or_default();
+
+        blocks.iter().enumerate().try_for_each(|(index, block)| {
+            self.apply_block(&mut utxo_set, block, first_height + index, db, caches)
+        })?;
+
+        Ok(utxo_set)
+    }
+}
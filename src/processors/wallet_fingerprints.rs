@@ -0,0 +1,207 @@
+use bitcoin::hashes::Hash;
+use bitcoin_explorer::{BitcoinDB, FBlock, FTransaction};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+    utils::{sighash_to_name, txin_to_signatures, Signature},
+};
+
+/// Any sequence below this one signals replaceability (BIP125)
+const MAX_RBF_SEQUENCE: u32 = 0xffff_fffd;
+
+/// Lock times below are heights, above are timestamps
+const LOCK_TIME_THRESHOLD: u32 = 500_000_000;
+
+/// Bitcoin Core sometimes sets the lock time up to 100 blocks back, to protect privacy
+const ANTI_FEE_SNIPING_MAX_DEPTH: usize = 100;
+
+/// Size of an ECDSA r value without its padding byte
+const LOW_R_MAX_SIZE: usize = 32;
+
+#[derive(Serialize, Deserialize)]
+pub struct WalletFingerprints {
+    transactions: usize,
+    sortable_transactions: usize,
+    ecdsa_transactions: usize,
+    rbf_share: f64,
+    anti_fee_sniping_share: f64,
+    version_1_share: f64,
+    version_2_share: f64,
+    bip69_share: f64,
+    low_r_share: f64,
+    sighash_types: BTreeMap<String, usize>,
+}
+
+pub type WalletFingerprintsProcessor = Processor<WalletFingerprints, WalletFingerprints>;
+
+impl WalletFingerprintsProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "WalletFingerprints".to_string(),
+            output: Output::new(path, "wallet_fingerprints.json"),
+            pd: PhantomData,
+        }
+    }
+}
+
+fn is_anti_fee_sniping(tx: &FTransaction, height: usize) -> bool {
+    let lock_time = tx.lock_time;
+
+    lock_time > 0
+        && lock_time < LOCK_TIME_THRESHOLD
+        && (lock_time as usize) <= height
+        && (lock_time as usize) + ANTI_FEE_SNIPING_MAX_DEPTH >= height
+}
+
+/// Inputs sorted by txid (in reversed byte order) then vout, outputs by value then script
+fn is_bip69(tx: &FTransaction) -> bool {
+    let inputs = tx
+        .input
+        .iter()
+        .map(|txin| {
+            let mut txid = txin.previous_output.txid.into_inner();
+
+            txid.reverse();
+
+            (txid, txin.previous_output.vout)
+        })
+        .collect::<Vec<_>>();
+
+    let outputs = tx
+        .output
+        .iter()
+        .map(|txout| (txout.value, txout.script_pubkey.as_bytes()))
+        .collect::<Vec<_>>();
+
+    inputs.windows(2).all(|pair| pair[0] <= pair[1])
+        && outputs.windows(2).all(|pair| pair[0] <= pair[1])
+}
+
+impl DailyBlocksProcessor<WalletFingerprints> for WalletFingerprintsProcessor {
+    ///
+    /// Shares of non coinbase transactions that:
+    /// - Signal RBF with at least one input
+    /// - Set their lock time to a recent height (anti fee sniping)
+    /// - Are version 1 or version 2
+    /// - Follow BIP69 ordering, among those with more than one input or output to sort
+    /// - Only have low-R ECDSA signatures, among those with at least one
+    ///
+    /// Sighash types count every signature found.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        _: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<WalletFingerprints> {
+        let first_height = self.block_to_height(blocks.first().unwrap(), db);
+
+        let mut transactions = 0;
+        let mut sortable_transactions = 0;
+        let mut ecdsa_transactions = 0;
+        let mut rbf = 0;
+        let mut anti_fee_sniping = 0;
+        let mut version_1 = 0;
+        let mut version_2 = 0;
+        let mut bip69 = 0;
+        let mut low_r = 0;
+        let mut sighash_types = BTreeMap::new();
+
+        blocks.iter().enumerate().for_each(|(index, block)| {
+            let height = first_height + index;
+
+            block
+                .txdata
+                .iter()
+                .filter(|tx| !tx.input.is_empty())
+                .for_each(|tx| {
+                    transactions += 1;
+
+                    if tx
+                        .input
+                        .iter()
+                        .any(|txin| txin.sequence <= MAX_RBF_SEQUENCE)
+                    {
+                        rbf += 1;
+                    }
+
+                    if is_anti_fee_sniping(tx, height) {
+                        anti_fee_sniping += 1;
+                    }
+
+                    match tx.version {
+                        1 => version_1 += 1,
+                        2 => version_2 += 1,
+                        _ => {}
+                    }
+
+                    if tx.input.len() > 1 || tx.output.len() > 1 {
+                        sortable_transactions += 1;
+
+                        if is_bip69(tx) {
+                            bip69 += 1;
+                        }
+                    }
+
+                    let signatures = tx
+                        .input
+                        .iter()
+                        .flat_map(txin_to_signatures)
+                        .collect::<Vec<_>>();
+
+                    let mut has_ecdsa = false;
+                    let mut all_low_r = true;
+
+                    signatures.into_iter().for_each(|signature| {
+                        let sighash = match signature {
+                            Signature::Ecdsa { r_size, sighash } => {
+                                has_ecdsa = true;
+                                all_low_r &= r_size <= LOW_R_MAX_SIZE;
+
+                                Some(sighash)
+                            }
+                            Signature::Schnorr { sighash } => sighash,
+                        };
+
+                        *sighash_types.entry(sighash_to_name(sighash)).or_default() += 1;
+                    });
+
+                    if has_ecdsa {
+                        ecdsa_transactions += 1;
+
+                        if all_low_r {
+                            low_r += 1;
+                        }
+                    }
+                });
+        });
+
+        let share = |count: usize, total: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f64 / total as f64
+            }
+        };
+
+        Ok(WalletFingerprints {
+            transactions,
+            sortable_transactions,
+            ecdsa_transactions,
+            rbf_share: share(rbf, transactions),
+            anti_fee_sniping_share: share(anti_fee_sniping, transactions),
+            version_1_share: share(version_1, transactions),
+            version_2_share: share(version_2, transactions),
+            bip69_share: share(bip69, sortable_transactions),
+            low_r_share: share(low_r, ecdsa_transactions),
+            sighash_types,
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use bitcoin_explorer::{BitcoinDB, FBlock, FTransaction};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    marker::PhantomData,
+};
+
+use crate::{
+    databases::Databases,
+    output::*,
+    processors::{DBCaches, DailyBlocksProcessor, Processor},
+};
+
+/// Minimum values moved, in bitcoins, for a transaction to count
+const WHALE_THRESHOLDS: [u64; 3] = [100, 1_000, 10_000];
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct WhaleCounter {
+    count: usize,
+    value: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Whales {
+    thresholds: BTreeMap<u64, WhaleCounter>,
+}
+
+pub type WhalesProcessor = Processor<Whales, Whales>;
+
+impl WhalesProcessor {
+    pub fn new(path: &str) -> Self {
+        Self {
+            name: "Whales".to_string(),
+            output: Output::new(path, "whales.json"),
+            pd: PhantomData,
+        }
+    }
+
+    /// Outputs minus change, change being any output going back to an input address
+    fn tx_to_value_moved(&self, tx: &FTransaction, db: &BitcoinDB, caches: &DBCaches) -> u64 {
+        let input_addresses = tx
+            .input
+            .iter()
+            .flat_map(|txin| {
+                let txid = txin.previous_output.txid;
+
+                let vout = txin.previous_output.vout;
+
+                let txprev = self.txid_to_tx(txid, db, caches);
+
+                let txout = txprev.output.get(vout as usize).unwrap();
+
+                txout.addresses.to_vec()
+            })
+            .collect::<HashSet<_>>();
+
+        tx.output
+            .iter()
+            .filter(|txout| {
+                !txout
+                    .addresses
+                    .iter()
+                    .any(|address| input_addresses.contains(address))
+            })
+            .map(|txout| txout.value)
+            .sum()
+    }
+}
+
+impl DailyBlocksProcessor<Whales> for WhalesProcessor {
+    ///
+    /// Number and total value moved of non coinbase transactions moving at least each threshold,
+    /// keyed by threshold in bitcoins, a transaction counting for every threshold it reaches.
+    ///
+    /// There is no price data yet, so thresholds can't be expressed in dollars.
+    ///
+    fn process_daily_blocks(
+        &self,
+        blocks: &[FBlock],
+        db: &BitcoinDB,
+        caches: &DBCaches,
+        _: &Databases,
+        _: &NaiveDate,
+    ) -> color_eyre::Result<Whales> {
+        let mut whales = Whales {
+            thresholds: WHALE_THRESHOLDS
+                .into_iter()
+                .map(|threshold| (threshold, WhaleCounter::default()))
+                .collect(),
+        };
+
+        blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter())
+            .filter(|tx| !tx.input.is_empty())
+            .for_each(|tx| {
+                let value = self.tx_to_value_moved(tx, db, caches);
+
+                whales
+                    .thresholds
+                    .iter_mut()
+                    .filter(|(threshold, _)| value >= **threshold * 100_000_000)
+                    .for_each(|(_, counter)| {
+                        counter.count += 1;
+                        counter.value += value;
+                    });
+            });
+
+        Ok(whales)
+    }
+}
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+use bitcoin_explorer::FTransaction;
+
+/// Value shared by the most outputs and how many share it, the highest value winning ties
+pub fn tx_to_most_common_output_value(tx: &FTransaction) -> Option<(u64, usize)> {
+    let mut counts: HashMap<u64, usize> = HashMap::new();
+
+    tx.output.iter().for_each(|txout| {
+        *counts.entry(txout.value).or_default() += 1;
+    });
+
+    counts
+        .into_iter()
+        .max_by_key(|(value, count)| (*count, *value))
+}
+
+///
+/// Several inputs and several outputs of the same value.
+///
+/// Purposely broad: it is used to avoid assuming that all inputs share an owner,
+/// where a false positive costs less than a false negative.
+///
+pub fn is_coinjoin_like(tx: &FTransaction) -> bool {
+    tx.input.len() >= 2 && tx_to_most_common_output_value(tx).is_some_and(|(_, count)| count >= 2)
+}
@@ -0,0 +1,23 @@
+use bitcoin_explorer::{BitcoinDB, Txid};
+use chrono::NaiveDate;
+
+use crate::processors::DBCaches;
+
+/// Bitcoin days destroyed by spending the output on that date
+pub fn outpoint_to_days_destroyed(
+    txid: Txid,
+    vout: u32,
+    date: &NaiveDate,
+    db: &BitcoinDB,
+    caches: &DBCaches,
+) -> f64 {
+    let value = caches.outpoint_to_value(txid, vout, db);
+
+    let bitcoins = (value as f64) / 100_000_000.0;
+
+    let prev_date = caches.txid_to_naive_date(txid, db);
+
+    let num_days = date.signed_duration_since(prev_date).num_days() as f64;
+
+    bitcoins * num_days
+}
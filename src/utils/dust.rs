@@ -0,0 +1,53 @@
+use bitcoin_explorer::Script;
+
+use crate::utils::is_unspendable;
+
+/// Fee rate used by Bitcoin Core to compute dust thresholds, in sats per vbyte
+const DUST_RELAY_FEE_RATE: u64 = 3;
+
+/// Outpoint, script length, sequence and a typical signature with its public key
+const LEGACY_SPEND_SIZE: u64 = 32 + 4 + 1 + 107 + 4;
+
+/// Same as legacy, the signature and public key being discounted as witness data
+const WITNESS_SPEND_SIZE: u64 = 32 + 4 + 1 + 107 / 4 + 4;
+
+/// Size of the output once serialized: value, script length and script
+pub fn script_to_txout_size(script: &Script) -> u64 {
+    let len = script.len() as u64;
+
+    let compact_size = match len {
+        0..=0xfc => 1,
+        0xfd..=0xffff => 3,
+        _ => 5,
+    };
+
+    8 + compact_size + len
+}
+
+/// Estimated size of the input spending the output, as assumed by Bitcoin Core
+pub fn script_to_spend_size(script: &Script) -> u64 {
+    if script.is_witness_program() {
+        WITNESS_SPEND_SIZE
+    } else {
+        LEGACY_SPEND_SIZE
+    }
+}
+
+///
+/// Minimum value for an output not to be dust, following Bitcoin Core
+/// (546 sats for P2PKH, 540 for P2SH, 294 for P2WPKH, 330 for P2WSH and P2TR).
+///
+/// Outputs Bitcoin Core considers unspendable are never dust.
+///
+pub fn script_to_dust_threshold(script: &Script) -> u64 {
+    if is_unspendable(script) {
+        0
+    } else {
+        (script_to_txout_size(script) + script_to_spend_size(script)) * DUST_RELAY_FEE_RATE
+    }
+}
+
+/// Whether spending the output at that fee rate (in sats per vbyte) costs more than it's worth
+pub fn is_uneconomical(value: u64, script: &Script, fee_rate: u64) -> bool {
+    !is_unspendable(script) && value < script_to_spend_size(script) * fee_rate
+}
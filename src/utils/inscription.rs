@@ -0,0 +1,78 @@
+use bitcoin::{
+    blockdata::{opcodes::all, script::Instruction},
+    Script,
+};
+
+const PROTOCOL_ID: &[u8] = b"ord";
+
+const CONTENT_TYPE_TAG: &[u8] = &[1];
+
+pub struct Inscription {
+    pub content_type: Option<String>,
+    pub body_size: usize,
+}
+
+///
+/// Parses every `OP_FALSE OP_IF "ord" ... OP_ENDIF` envelope of a tapscript.
+///
+/// Fields are tag/value pairs until an empty push, after which every push is part of the body.
+///
+pub fn tapscript_to_inscriptions(script: &Script) -> Vec<Inscription> {
+    let instructions = script
+        .instructions()
+        .map_while(Result::ok)
+        .collect::<Vec<_>>();
+
+    let mut inscriptions = vec![];
+
+    let mut index = 0;
+
+    while index + 2 < instructions.len() {
+        let is_envelope_start = matches!(instructions[index], Instruction::PushBytes([]))
+            && instructions[index + 1] == Instruction::Op(all::OP_IF)
+            && instructions[index + 2] == Instruction::PushBytes(PROTOCOL_ID);
+
+        if !is_envelope_start {
+            index += 1;
+            continue;
+        }
+
+        index += 3;
+
+        let mut inscription = Inscription {
+            content_type: None,
+            body_size: 0,
+        };
+
+        let mut in_body = false;
+
+        while let Some(instruction) = instructions.get(index) {
+            index += 1;
+
+            match instruction {
+                Instruction::Op(op) if *op == all::OP_ENDIF => break,
+                Instruction::PushBytes(bytes) if in_body => inscription.body_size += bytes.len(),
+                Instruction::PushBytes([]) => in_body = true,
+                tag => {
+                    let is_content_type = match tag {
+                        Instruction::PushBytes(bytes) => *bytes == CONTENT_TYPE_TAG,
+                        Instruction::Op(op) => *op == all::OP_PUSHNUM_1,
+                    };
+
+                    if let Some(Instruction::PushBytes(value)) = instructions.get(index) {
+                        if is_content_type {
+                            inscription.content_type =
+                                Some(String::from_utf8_lossy(value).into_owned());
+                        }
+
+                        index += 1;
+                    }
+                }
+            }
+        }
+
+        inscriptions.push(inscription);
+    }
+
+    inscriptions
+}
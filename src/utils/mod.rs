@@ -1,5 +1,27 @@
+mod coinjoin;
 mod date;
+mod days_destroyed;
+mod dust;
 mod group;
+mod inscription;
+mod script_template;
+mod signature;
+mod snapshot;
+mod subsidy;
+mod utxo;
+mod weight;
+mod witness;
 
+pub use coinjoin::*;
 pub use date::*;
+pub use days_destroyed::*;
+pub use dust::*;
 pub use group::*;
+pub use inscription::*;
+pub use script_template::*;
+pub use signature::*;
+pub use snapshot::*;
+pub use subsidy::*;
+pub use utxo::*;
+pub use weight::*;
+pub use witness::*;
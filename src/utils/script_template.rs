@@ -0,0 +1,254 @@
+use bitcoin::{
+    blockdata::{
+        opcodes::{all, All},
+        script::Instruction,
+    },
+    Script, TxIn,
+};
+
+use crate::utils::witness_to_tapscript;
+
+const COMPRESSED_PUBKEY_SIZE: usize = 33;
+
+const UNCOMPRESSED_PUBKEY_SIZE: usize = 65;
+
+const X_ONLY_PUBKEY_SIZE: usize = 32;
+
+const P2WSH_PROGRAM_SIZE: usize = 34;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SpendKind {
+    P2sh,
+    P2shP2wsh,
+    P2wsh,
+    Tapscript,
+}
+
+#[derive(PartialEq, Eq)]
+pub enum ScriptTemplate {
+    LightningFunding,
+    LightningToLocal,
+    LightningHtlc,
+    Htlc,
+    Multisig { m: u8, n: u8 },
+    Cltv,
+    Csv,
+    Other,
+}
+
+impl ScriptTemplate {
+    pub fn name(&self) -> String {
+        match self {
+            Self::LightningFunding => "lightning_funding".to_string(),
+            Self::LightningToLocal => "lightning_to_local".to_string(),
+            Self::LightningHtlc => "lightning_htlc".to_string(),
+            Self::Htlc => "htlc".to_string(),
+            Self::Multisig { m, n } => format!("multisig_{m}_of_{n}"),
+            Self::Cltv => "cltv".to_string(),
+            Self::Csv => "csv".to_string(),
+            Self::Other => "other".to_string(),
+        }
+    }
+}
+
+/// Script revealed when spending a script hash (redeem or witness script) or a tapscript leaf
+pub fn txin_to_revealed_script(txin: &TxIn, prevout: &Script) -> Option<(Script, SpendKind)> {
+    if prevout.is_p2sh() {
+        let redeem_script = txin
+            .script_sig
+            .instructions()
+            .map_while(Result::ok)
+            .last()
+            .and_then(|instruction| match instruction {
+                Instruction::PushBytes(bytes) => Some(Script::from(bytes.to_vec())),
+                Instruction::Op(_) => None,
+            })?;
+
+        if txin.witness.is_empty() {
+            Some((redeem_script, SpendKind::P2sh))
+        } else if redeem_script.len() == P2WSH_PROGRAM_SIZE && redeem_script.is_v0_p2wsh() {
+            txin.witness
+                .last()
+                .map(|script| (Script::from(script.to_vec()), SpendKind::P2shP2wsh))
+        } else {
+            None
+        }
+    } else if prevout.is_v0_p2wsh() {
+        txin.witness
+            .last()
+            .map(|script| (Script::from(script.to_vec()), SpendKind::P2wsh))
+    } else if prevout.is_v1_p2tr() {
+        witness_to_tapscript(&txin.witness).map(|script| (script, SpendKind::Tapscript))
+    } else {
+        None
+    }
+}
+
+fn pushnum_to_number(instruction: &Instruction) -> Option<u8> {
+    match instruction {
+        Instruction::Op(op)
+            if (all::OP_PUSHNUM_1.into_u8()..=all::OP_PUSHNUM_16.into_u8())
+                .contains(&op.into_u8()) =>
+        {
+            Some(op.into_u8() - all::OP_PUSHNUM_1.into_u8() + 1)
+        }
+        _ => None,
+    }
+}
+
+fn is_compressed_pubkey(instruction: &Instruction) -> bool {
+    matches!(instruction, Instruction::PushBytes(bytes) if bytes.len() == COMPRESSED_PUBKEY_SIZE)
+}
+
+/// Compressed or uncompressed, as found in legacy and v0 scripts
+fn is_pubkey(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::PushBytes(bytes)
+            if bytes.len() == COMPRESSED_PUBKEY_SIZE || bytes.len() == UNCOMPRESSED_PUBKEY_SIZE
+    )
+}
+
+fn is_x_only_pubkey(instruction: &Instruction) -> bool {
+    matches!(instruction, Instruction::PushBytes(bytes) if bytes.len() == X_ONLY_PUBKEY_SIZE)
+}
+
+fn is_op(instruction: &Instruction, op: All) -> bool {
+    *instruction == Instruction::Op(op)
+}
+
+///
+/// `m <pubkeys...> n OP_CHECKMULTISIG` or the tapscript equivalent
+/// `<pubkey> OP_CHECKSIG <pubkey> OP_CHECKSIGADD ... m OP_NUMEQUAL` with x-only keys
+///
+fn instructions_to_multisig(instructions: &[Instruction]) -> Option<(u8, u8)> {
+    match instructions {
+        [first, pubkeys @ .., last_number, checkmultisig]
+            if is_op(checkmultisig, all::OP_CHECKMULTISIG) && pubkeys.iter().all(is_pubkey) =>
+        {
+            let m = pushnum_to_number(first)?;
+
+            let n = pushnum_to_number(last_number)?;
+
+            (usize::from(n) == pubkeys.len()).then_some((m, n))
+        }
+        [rest @ .., number, numequal] if is_op(numequal, all::OP_NUMEQUAL) => {
+            let m = pushnum_to_number(number)?;
+
+            let is_checksigadd_chain = !rest.is_empty()
+                && rest.len().is_multiple_of(2)
+                && rest.chunks(2).enumerate().all(|(index, pair)| {
+                    let op = if index == 0 {
+                        all::OP_CHECKSIG
+                    } else {
+                        all::OP_CHECKSIGADD
+                    };
+
+                    is_x_only_pubkey(&pair[0]) && is_op(&pair[1], op)
+                });
+
+            let n = rest.len() / 2;
+
+            (is_checksigadd_chain && n <= u8::MAX as usize).then_some((m, n as u8))
+        }
+        _ => None,
+    }
+}
+
+/// `2 <pubkey1> <pubkey2> 2 OP_CHECKMULTISIG` with compressed keys sorted lexicographically (BOLT3)
+fn is_lightning_funding(instructions: &[Instruction]) -> bool {
+    match instructions {
+        [_, Instruction::PushBytes(pubkey1), Instruction::PushBytes(pubkey2), _, _] => {
+            instructions_to_multisig(instructions) == Some((2, 2))
+                && pubkey1.len() == COMPRESSED_PUBKEY_SIZE
+                && pubkey2.len() == COMPRESSED_PUBKEY_SIZE
+                && pubkey1 < pubkey2
+        }
+        _ => false,
+    }
+}
+
+///
+/// Matches a revealed script against known templates, the first match winning.
+///
+/// Lightning templates are those of BOLT3: the funding output is a 2-of-2 P2WSH multisig
+/// of sorted compressed keys,
+/// `to_local` is a revocable output delayed with `OP_CSV` and HTLCs start by checking
+/// the revocation key hash.
+///
+pub fn script_to_template(script: &Script, kind: SpendKind) -> ScriptTemplate {
+    let instructions = script
+        .instructions()
+        .map_while(Result::ok)
+        .collect::<Vec<_>>();
+
+    let has_op = |op| {
+        instructions
+            .iter()
+            .any(|instruction| is_op(instruction, op))
+    };
+
+    let has_cltv = has_op(all::OP_CLTV);
+
+    let has_csv = has_op(all::OP_CSV);
+
+    if let Some((m, n)) = instructions_to_multisig(&instructions) {
+        if kind == SpendKind::P2wsh && is_lightning_funding(&instructions) {
+            return ScriptTemplate::LightningFunding;
+        }
+
+        return ScriptTemplate::Multisig { m, n };
+    }
+
+    let is_to_local = instructions.len() == 9
+        && is_op(&instructions[0], all::OP_IF)
+        && is_compressed_pubkey(&instructions[1])
+        && is_op(&instructions[2], all::OP_ELSE)
+        && is_op(&instructions[4], all::OP_CSV)
+        && is_op(&instructions[5], all::OP_DROP)
+        && is_compressed_pubkey(&instructions[6])
+        && is_op(&instructions[7], all::OP_ENDIF)
+        && is_op(&instructions[8], all::OP_CHECKSIG);
+
+    if is_to_local {
+        return ScriptTemplate::LightningToLocal;
+    }
+
+    let is_lightning_htlc = instructions.len() > 7
+        && is_op(&instructions[0], all::OP_DUP)
+        && is_op(&instructions[1], all::OP_HASH160)
+        && matches!(instructions[2], Instruction::PushBytes(bytes) if bytes.len() == 20)
+        && is_op(&instructions[3], all::OP_EQUAL)
+        && is_op(&instructions[4], all::OP_IF)
+        && is_op(&instructions[5], all::OP_CHECKSIG)
+        && is_op(&instructions[6], all::OP_ELSE);
+
+    if is_lightning_htlc {
+        return ScriptTemplate::LightningHtlc;
+    }
+
+    let has_hashlock = has_op(all::OP_SHA256) || has_op(all::OP_HASH160);
+
+    if has_hashlock && (has_cltv || has_csv) {
+        ScriptTemplate::Htlc
+    } else if has_cltv {
+        ScriptTemplate::Cltv
+    } else if has_csv {
+        ScriptTemplate::Csv
+    } else {
+        ScriptTemplate::Other
+    }
+}
+
+/// Whether the script uses `OP_CHECKLOCKTIMEVERIFY` and `OP_CHECKSEQUENCEVERIFY`
+pub fn script_to_timelocks(script: &Script) -> (bool, bool) {
+    script
+        .instructions()
+        .map_while(Result::ok)
+        .fold((false, false), |(cltv, csv), instruction| {
+            (
+                cltv || is_op(&instruction, all::OP_CLTV),
+                csv || is_op(&instruction, all::OP_CSV),
+            )
+        })
+}
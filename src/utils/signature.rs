@@ -0,0 +1,82 @@
+use bitcoin::{blockdata::script::Instruction, TxIn};
+
+use crate::utils::witness_to_elements;
+
+const DER_SEQUENCE_TAG: u8 = 0x30;
+
+const DER_INTEGER_TAG: u8 = 0x02;
+
+const SCHNORR_SIGNATURE_SIZE: usize = 64;
+
+pub enum Signature {
+    Ecdsa { r_size: usize, sighash: u8 },
+    Schnorr { sighash: Option<u8> },
+}
+
+/// DER encoded ECDSA signature followed by its sighash byte
+fn parse_ecdsa_signature(bytes: &[u8]) -> Option<Signature> {
+    if bytes.len() < 9 || bytes[0] != DER_SEQUENCE_TAG || usize::from(bytes[1]) + 3 != bytes.len() {
+        return None;
+    }
+
+    if bytes[2] != DER_INTEGER_TAG {
+        return None;
+    }
+
+    Some(Signature::Ecdsa {
+        r_size: usize::from(bytes[3]),
+        sighash: *bytes.last().unwrap(),
+    })
+}
+
+///
+/// Signatures found in the script sig pushes and the witness of an input.
+///
+/// A lone witness element of 64 or 65 bytes is assumed to be a taproot key path signature.
+///
+pub fn txin_to_signatures(txin: &TxIn) -> Vec<Signature> {
+    let mut signatures = txin
+        .script_sig
+        .instructions()
+        .map_while(Result::ok)
+        .filter_map(|instruction| match instruction {
+            Instruction::PushBytes(bytes) => parse_ecdsa_signature(bytes),
+            Instruction::Op(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    let elements = witness_to_elements(&txin.witness);
+
+    match elements.as_slice() {
+        [element] if element.len() == SCHNORR_SIGNATURE_SIZE => {
+            signatures.push(Signature::Schnorr { sighash: None })
+        }
+        [element] if element.len() == SCHNORR_SIGNATURE_SIZE + 1 => {
+            signatures.push(Signature::Schnorr {
+                sighash: element.last().copied(),
+            })
+        }
+        _ => signatures.extend(elements.into_iter().filter_map(parse_ecdsa_signature)),
+    }
+
+    signatures
+}
+
+pub fn sighash_to_name(sighash: Option<u8>) -> String {
+    let Some(sighash) = sighash else {
+        return "DEFAULT".to_string();
+    };
+
+    let base = match sighash & 0x1f {
+        0x01 => "ALL",
+        0x02 => "NONE",
+        0x03 => "SINGLE",
+        _ => return format!("UNKNOWN({sighash:#04x})"),
+    };
+
+    if sighash & 0x80 != 0 {
+        format!("{base}|ANYONECANPAY")
+    } else {
+        base.to_string()
+    }
+}
@@ -0,0 +1,354 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use bitcoin::{hashes::Hash, secp256k1::PublicKey, BlockHash, Script, TxOut};
+use color_eyre::eyre::eyre;
+
+/// Snapshots written since Bitcoin Core v28 start with these bytes
+const SNAPSHOT_MAGIC: [u8; 5] = *b"utxo\xff";
+
+const MAX_SCRIPT_SIZE: u64 = 10_000;
+
+/// Number of special script types compressed by Bitcoin Core
+const SPECIAL_SCRIPTS: u64 = 6;
+
+///
+/// UTXO set written by `bitcoin-cli dumptxoutset`.
+///
+/// Both formats are supported, coins grouped by txid since v28 and one outpoint per coin before.
+///
+pub struct Snapshot<R = BufReader<File>> {
+    pub block_hash: BlockHash,
+    pub coins_count: u64,
+    reader: R,
+    is_grouped_by_txid: bool,
+}
+
+impl Snapshot {
+    pub fn open(path: &Path) -> color_eyre::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> Snapshot<R> {
+    fn from_reader(mut reader: R) -> color_eyre::Result<Self> {
+        let mut start = [0; 5];
+
+        reader.read_exact(&mut start)?;
+
+        let is_grouped_by_txid = start == SNAPSHOT_MAGIC;
+
+        let block_hash = if is_grouped_by_txid {
+            // Version and network magic
+            read_bytes::<6>(&mut reader)?;
+
+            read_bytes::<32>(&mut reader)?
+        } else {
+            let mut block_hash = [0; 32];
+
+            block_hash[..5].copy_from_slice(&start);
+            block_hash[5..].copy_from_slice(&read_bytes::<27>(&mut reader)?);
+
+            block_hash
+        };
+
+        Ok(Self {
+            block_hash: BlockHash::from_inner(block_hash),
+            coins_count: u64::from_le_bytes(read_bytes::<8>(&mut reader)?),
+            reader,
+            is_grouped_by_txid,
+        })
+    }
+
+    /// Calls the closure with the output of every coin, outpoints, heights and coinbase flags being skipped
+    pub fn for_each_txout(mut self, mut f: impl FnMut(TxOut)) -> color_eyre::Result<()> {
+        let reader = &mut self.reader;
+
+        let mut coins_left = self.coins_count;
+
+        while coins_left > 0 {
+            // Txid
+            read_bytes::<32>(reader)?;
+
+            let coins = if self.is_grouped_by_txid {
+                read_compact_size(reader)?
+            } else {
+                // Vout
+                read_bytes::<4>(reader)?;
+
+                1
+            };
+
+            for _ in 0..coins {
+                if self.is_grouped_by_txid {
+                    read_compact_size(reader)?;
+                }
+
+                // Height and coinbase flag
+                read_varint(reader)?;
+
+                let value = decompress_amount(read_varint(reader)?);
+
+                let script_pubkey = read_compressed_script(reader)?;
+
+                f(TxOut {
+                    value,
+                    script_pubkey,
+                });
+            }
+
+            coins_left = coins_left
+                .checked_sub(coins)
+                .ok_or_else(|| eyre!("More coins than announced in the snapshot"))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> color_eyre::Result<[u8; N]> {
+    let mut bytes = [0; N];
+
+    reader.read_exact(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+fn read_compact_size(reader: &mut impl Read) -> color_eyre::Result<u64> {
+    let [first] = read_bytes::<1>(reader)?;
+
+    Ok(match first {
+        0xfd => u16::from_le_bytes(read_bytes(reader)?) as u64,
+        0xfe => u32::from_le_bytes(read_bytes(reader)?) as u64,
+        0xff => u64::from_le_bytes(read_bytes(reader)?),
+        _ => first as u64,
+    })
+}
+
+/// Bitcoin Core's own variable length integer, most significant bits first
+fn read_varint(reader: &mut impl Read) -> color_eyre::Result<u64> {
+    let mut n = 0_u64;
+
+    loop {
+        let [byte] = read_bytes::<1>(reader)?;
+
+        n = (n << 7) | (byte & 0x7f) as u64;
+
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+
+        n += 1;
+    }
+}
+
+fn decompress_amount(x: u64) -> u64 {
+    if x == 0 {
+        return 0;
+    }
+
+    let mut x = x - 1;
+
+    let exponent = x % 10;
+
+    x /= 10;
+
+    let mantissa = if exponent < 9 {
+        let digit = x % 9 + 1;
+
+        x /= 9;
+
+        x * 10 + digit
+    } else {
+        x + 1
+    };
+
+    mantissa * 10_u64.pow(exponent as u32)
+}
+
+fn read_compressed_script(reader: &mut impl Read) -> color_eyre::Result<Script> {
+    let size = read_varint(reader)?;
+
+    let bytes = match size {
+        0 => [
+            &[0x76, 0xa9, 0x14][..],
+            &read_bytes::<20>(reader)?,
+            &[0x88, 0xac],
+        ]
+        .concat(),
+        1 => [&[0xa9, 0x14][..], &read_bytes::<20>(reader)?, &[0x87]].concat(),
+        2 | 3 => [&[0x21, size as u8][..], &read_bytes::<32>(reader)?, &[0xac]].concat(),
+        4 | 5 => {
+            let compressed = [&[size as u8 - 2][..], &read_bytes::<32>(reader)?].concat();
+
+            let public_key = PublicKey::from_slice(&compressed)?;
+
+            [&[0x41][..], &public_key.serialize_uncompressed(), &[0xac]].concat()
+        }
+        _ => {
+            let size = size - SPECIAL_SCRIPTS;
+
+            let mut bytes = vec![];
+
+            reader.by_ref().take(size).read_to_end(&mut bytes)?;
+
+            // Overly long scripts are replaced by an unspendable one
+            if size > MAX_SCRIPT_SIZE {
+                vec![0x6a]
+            } else {
+                bytes
+            }
+        }
+    };
+
+    Ok(Script::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TXID: [u8; 32] = [0x11; 32];
+
+    const BLOCK_HASH: [u8; 32] = [0x22; 32];
+
+    /// Generator point of secp256k1, whose y is even
+    const G_X: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    const G_Y: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    fn hex(string: &str) -> Vec<u8> {
+        (0..string.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&string[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Coin at height 100, not a coinbase, of 50 BTC locked to a P2PKH
+    fn coin() -> Vec<u8> {
+        [&[0x80, 0x48, 0x32, 0x00][..], &[0xab; 20]].concat()
+    }
+
+    fn p2pkh() -> Script {
+        Script::from([&[0x76, 0xa9, 0x14][..], &[0xab; 20], &[0x88, 0xac]].concat())
+    }
+
+    fn snapshot_to_txouts(bytes: &[u8]) -> (BlockHash, Vec<TxOut>) {
+        let snapshot = Snapshot::from_reader(bytes).unwrap();
+
+        let block_hash = snapshot.block_hash;
+
+        let mut txouts = vec![];
+
+        snapshot.for_each_txout(|txout| txouts.push(txout)).unwrap();
+
+        (block_hash, txouts)
+    }
+
+    #[test]
+    fn decompresses_amounts() {
+        // Vectors of Bitcoin Core's compress_tests.cpp
+        [
+            (0x0, 0),
+            (0x1, 1),
+            (0x7, 1_000_000),
+            (0x9, 100_000_000),
+            (0x32, 5_000_000_000),
+            (0x1406f40, 2_100_000_000_000_000),
+        ]
+        .into_iter()
+        .for_each(|(compressed, amount)| assert_eq!(decompress_amount(compressed), amount));
+    }
+
+    #[test]
+    fn reads_varints() {
+        // Vectors of Bitcoin Core's serialize_tests.cpp
+        [
+            ("00", 0),
+            ("7f", 0x7f),
+            ("8000", 0x80),
+            ("a334", 0x1234),
+            ("82fe7f", 0xffff),
+            ("c7e756", 0x123456),
+            ("86ffc7e756", 0x80123456),
+            ("8efefefe7f", 0xffffffff),
+            ("fefefefefefefefe7f", 0x7fffffffffffffff),
+            ("80fefefefefefefefe7f", 0xffffffffffffffff),
+        ]
+        .into_iter()
+        .for_each(|(bytes, n)| assert_eq!(read_varint(&mut &hex(bytes)[..]).unwrap(), n));
+    }
+
+    #[test]
+    fn reads_compressed_scripts() {
+        let read = |bytes: Vec<u8>| read_compressed_script(&mut &bytes[..]).unwrap();
+
+        assert_eq!(read([&[0x00][..], &[0xab; 20]].concat()), p2pkh());
+
+        assert_eq!(
+            read([&[0x01][..], &[0xcd; 20]].concat()),
+            Script::from([&[0xa9, 0x14][..], &[0xcd; 20], &[0x87]].concat())
+        );
+
+        assert_eq!(
+            read([&[0x02][..], &hex(G_X)].concat()),
+            Script::from([&[0x21, 0x02][..], &hex(G_X), &[0xac]].concat())
+        );
+
+        assert_eq!(
+            read([&[0x04][..], &hex(G_X)].concat()),
+            Script::from([&[0x41, 0x04][..], &hex(G_X), &hex(G_Y), &[0xac]].concat())
+        );
+
+        // Any other script is prefixed by its size plus the 6 special types
+        assert_eq!(read(vec![0x07, 0x51]), Script::from(vec![0x51]));
+    }
+
+    #[test]
+    fn reads_snapshots_grouped_by_txid() {
+        let bytes = [
+            &SNAPSHOT_MAGIC[..],
+            &2_u16.to_le_bytes(),
+            &[0xf9, 0xbe, 0xb4, 0xd9],
+            &BLOCK_HASH,
+            &2_u64.to_le_bytes(),
+            &TXID,
+            // Two coins, vouts 0 and 1
+            &[0x02, 0x00],
+            &coin(),
+            &[0x01],
+            &coin(),
+        ]
+        .concat();
+
+        let (block_hash, txouts) = snapshot_to_txouts(&bytes);
+
+        assert_eq!(block_hash, BlockHash::from_inner(BLOCK_HASH));
+        assert_eq!(txouts.len(), 2);
+        assert!(txouts
+            .iter()
+            .all(|txout| txout.value == 5_000_000_000 && txout.script_pubkey == p2pkh()));
+    }
+
+    #[test]
+    fn reads_legacy_snapshots() {
+        let bytes = [
+            &BLOCK_HASH[..],
+            &1_u64.to_le_bytes(),
+            &TXID,
+            &0_u32.to_le_bytes(),
+            &coin(),
+        ]
+        .concat();
+
+        let (block_hash, txouts) = snapshot_to_txouts(&bytes);
+
+        assert_eq!(block_hash, BlockHash::from_inner(BLOCK_HASH));
+        assert_eq!(txouts.len(), 1);
+        assert_eq!(txouts[0].value, 5_000_000_000);
+        assert_eq!(txouts[0].script_pubkey, p2pkh());
+    }
+}
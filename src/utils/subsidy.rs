@@ -0,0 +1,79 @@
+use bitcoin_explorer::{BitcoinDB, FBlock};
+
+use crate::processors::DBCaches;
+
+const INITIAL_SUBSIDY: u64 = 50 * 100_000_000;
+
+const HALVING_INTERVAL: usize = 210_000;
+
+pub fn height_to_subsidy(height: usize) -> u64 {
+    let halvings = height / HALVING_INTERVAL;
+
+    if halvings >= 64 {
+        0
+    } else {
+        INITIAL_SUBSIDY >> halvings
+    }
+}
+
+/// Sum of the subsidies of every block up to the given height (included)
+pub fn height_to_theoretical_supply(height: usize) -> u64 {
+    let halvings = height / HALVING_INTERVAL;
+
+    let full_eras = (0..halvings)
+        .map(|era| height_to_subsidy(era * HALVING_INTERVAL) * HALVING_INTERVAL as u64)
+        .sum::<u64>();
+
+    let blocks_in_current_era = (height % HALVING_INTERVAL + 1) as u64;
+
+    full_eras + height_to_subsidy(height) * blocks_in_current_era
+}
+
+/// What the coinbase of a block was allowed to claim and what it did
+pub struct BlockClaim {
+    pub subsidy: u64,
+    pub fees: u64,
+    pub claimed: u64,
+}
+
+impl BlockClaim {
+    pub fn allowed(&self) -> u64 {
+        self.subsidy + self.fees
+    }
+
+    /// Subsidy and fees left unclaimed, lost forever
+    pub fn under_claimed(&self) -> u64 {
+        self.allowed().saturating_sub(self.claimed)
+    }
+
+    /// New coins actually created, the rest of the claim being fees
+    pub fn issued(&self) -> u64 {
+        self.claimed.saturating_sub(self.fees)
+    }
+}
+
+pub fn block_to_claim(
+    block: &FBlock,
+    height: usize,
+    db: &BitcoinDB,
+    caches: &DBCaches,
+) -> BlockClaim {
+    let fees = block
+        .txdata
+        .iter()
+        .map(|tx| caches.tx_to_fees(tx, db))
+        .sum::<u64>();
+
+    let claimed = block
+        .txdata
+        .iter()
+        .filter(|tx| tx.input.is_empty())
+        .flat_map(|tx| tx.output.iter().map(|txout| txout.value))
+        .sum::<u64>();
+
+    BlockClaim {
+        subsidy: height_to_subsidy(height),
+        fees,
+        claimed,
+    }
+}
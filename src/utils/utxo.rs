@@ -0,0 +1,20 @@
+use bitcoin_explorer::Script;
+
+/// Coinbases of these blocks have the same txid as an older one (91812 and 91722),
+/// whose outputs were overwritten before being spent (BIP30)
+pub const BIP30_DUPLICATE_COINBASE_HEIGHTS: [usize; 2] = [91842, 91880];
+
+const MAX_SCRIPT_SIZE: usize = 10_000;
+
+/// Outpoint, height and coinbase flag, value and script length, as counted by Bitcoin Core
+const BOGOSIZE_OVERHEAD: u64 = 32 + 4 + 4 + 8 + 2;
+
+/// Outputs Bitcoin Core never adds to its UTXO set
+pub fn is_unspendable(script: &Script) -> bool {
+    script.as_bytes().first() == Some(&0x6a) || script.len() > MAX_SCRIPT_SIZE
+}
+
+/// Size of an unspent output as estimated by `gettxoutsetinfo`
+pub fn script_to_bogosize(script: &Script) -> u64 {
+    BOGOSIZE_OVERHEAD + script.len() as u64
+}
@@ -0,0 +1,55 @@
+use bitcoin::VarInt;
+use bitcoin_explorer::FTransaction;
+
+use crate::utils::script_to_txout_size;
+
+/// Outpoint and sequence of an input, around its script
+const TXIN_FIXED_SIZE: usize = 32 + 4 + 4;
+
+/// Version and lock time
+const TX_FIXED_SIZE: usize = 4 + 4;
+
+/// Segwit marker and flag
+const WITNESS_HEADER_SIZE: usize = 2;
+
+/// Weight of the transaction, its non witness data counting 4 units per byte (BIP141)
+pub fn tx_to_weight(tx: &FTransaction) -> usize {
+    let inputs_size = tx
+        .input
+        .iter()
+        .map(|txin| {
+            let script_size = txin.script_sig.len();
+
+            TXIN_FIXED_SIZE + VarInt(script_size as u64).len() + script_size
+        })
+        .sum::<usize>();
+
+    let outputs_size = tx
+        .output
+        .iter()
+        .map(|txout| script_to_txout_size(&txout.script_pubkey) as usize)
+        .sum::<usize>();
+
+    let base_size = TX_FIXED_SIZE
+        + VarInt(tx.input.len() as u64).len()
+        + inputs_size
+        + VarInt(tx.output.len() as u64).len()
+        + outputs_size;
+
+    let witness_size = if tx.input.iter().all(|txin| txin.witness.is_empty()) {
+        0
+    } else {
+        WITNESS_HEADER_SIZE
+            + tx.input
+                .iter()
+                .map(|txin| txin.witness.serialized_len())
+                .sum::<usize>()
+    };
+
+    base_size * 4 + witness_size
+}
+
+/// Virtual size, the weight divided by 4 and rounded up
+pub fn tx_to_vsize(tx: &FTransaction) -> usize {
+    tx_to_weight(tx).div_ceil(4)
+}
@@ -0,0 +1,44 @@
+use bitcoin::{Script, Witness};
+
+const ANNEX_TAG: u8 = 0x50;
+
+const TAPROOT_LEAF_MASK: u8 = 0xfe;
+
+const TAPROOT_LEAF_TAPSCRIPT: u8 = 0xc0;
+
+const TAPROOT_CONTROL_BASE_SIZE: usize = 33;
+
+const TAPROOT_CONTROL_NODE_SIZE: usize = 32;
+
+/// Witness elements, without the annex of a taproot spend if there is one
+pub fn witness_to_elements(witness: &Witness) -> Vec<&[u8]> {
+    let mut elements = witness.iter().collect::<Vec<_>>();
+
+    if elements.len() >= 2 && elements.last().unwrap().first() == Some(&ANNEX_TAG) {
+        elements.pop();
+    }
+
+    elements
+}
+
+///
+/// Script of a taproot script path spend, which ends with the script and its control block.
+///
+/// Doesn't check that the output spent is P2TR.
+///
+pub fn witness_to_tapscript(witness: &Witness) -> Option<Script> {
+    let elements = witness_to_elements(witness);
+
+    if elements.len() < 2 {
+        return None;
+    }
+
+    let control_block = elements[elements.len() - 1];
+
+    let is_control_block = control_block.len() >= TAPROOT_CONTROL_BASE_SIZE
+        && (control_block.len() - TAPROOT_CONTROL_BASE_SIZE)
+            .is_multiple_of(TAPROOT_CONTROL_NODE_SIZE)
+        && control_block[0] & TAPROOT_LEAF_MASK == TAPROOT_LEAF_TAPSCRIPT;
+
+    is_control_block.then(|| Script::from(elements[elements.len() - 2].to_vec()))
+}